@@ -1,7 +1,6 @@
-use webhook::client::{WebhookClient, WebhookResult};
-use webhook::models::NonLinkButtonStyle;
+use webhook::prelude::*;
 
-const IMAGE_URL: &'static str = "https://cdn.discordapp.com/avatars/312157715449249795/a_b8b3b0c35f3dee2b6586a0dd58697e29.png";
+const IMAGE_URL: &str = "https://cdn.discordapp.com/avatars/312157715449249795/a_b8b3b0c35f3dee2b6586a0dd58697e29.png";
 
 #[tokio::main]
 async fn main() -> WebhookResult<()> {
@@ -19,10 +18,10 @@ async fn main() -> WebhookResult<()> {
         .embed(|embed| embed
             .title("Webhook")
             .description("Hello, World!")
-            .footer("Footer", Some(String::from(IMAGE_URL)))
+            .footer("Footer", IMAGE_URL.to_owned())
             .image(IMAGE_URL)
             .thumbnail(IMAGE_URL)
-            .author("Lmao#0001", Some(String::from(IMAGE_URL)), Some(String::from(IMAGE_URL)))
+            .author("Lmao#0001", IMAGE_URL.to_owned(), IMAGE_URL.to_owned())
             .field("name", "value", false))).await?;
 
     Ok(())
@@ -30,8 +29,9 @@ async fn main() -> WebhookResult<()> {
 
 // to try out using application webhook run:
 // `application_webhook_example(&url).await?;`
+#[allow(dead_code)]
 async fn application_webhook_example(url: &str) -> WebhookResult<()> {
-    let client = WebhookClient::new(&url);
+    let client = WebhookClient::new(url);
     let webhook_info = client.get_information().await?;
     println!("webhook: {:?}", webhook_info);
 
@@ -45,14 +45,14 @@ async fn application_webhook_example(url: &str) -> WebhookResult<()> {
                         button
                             .style(NonLinkButtonStyle::Primary)
                             .label("Primary!")
-                            .emoji("625891304081063986", "mage", false)
+                            .emoji(625891304081063986, "mage", false)
                             .custom_id("id_0")
                     })
                         .regular_button(|button| {
                             button
                                 .style(NonLinkButtonStyle::Secondary)
                                 .label("Secondary!")
-                                .emoji("625891304081063986", "mage", false)
+                                .emoji(625891304081063986, "mage", false)
                                 .custom_id("id_1")
                         })
                         .link_button(|button| button.label("Click Me!").url("https://discord.com"))
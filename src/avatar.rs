@@ -0,0 +1,128 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fmt;
+use std::path::Path;
+
+use crate::WebhookResult;
+
+/// The image formats Discord accepts for webhook avatars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+}
+
+impl ImageFormat {
+    /// Detects the format from the magic bytes at the start of the image.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFormat::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else {
+            None
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Gif => "image/gif",
+        }
+    }
+}
+
+/// An avatar image, encoded as the data URI Discord expects when creating or modifying a webhook.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # async fn run(client: WebhookClient) -> WebhookResult<()> {
+/// let avatar = Avatar::from_path("assets/bot.png")?;
+/// client.modify(|webhook| webhook.avatar(&avatar)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Avatar {
+    format: ImageFormat,
+    data_uri: String,
+}
+
+impl Avatar {
+    /// The maximum size of an avatar image in bytes.
+    pub const MAX_LEN: usize = 10 * 1024 * 1024;
+
+    pub fn from_bytes(bytes: &[u8]) -> WebhookResult<Self> {
+        if bytes.len() > Self::MAX_LEN {
+            return Err(format!(
+                "Avatar image size ({} bytes) exceeds the maximum of {} bytes",
+                bytes.len(),
+                Self::MAX_LEN
+            )
+            .into());
+        }
+        let format =
+            ImageFormat::detect(bytes).ok_or("Avatar image is not a PNG, JPEG or GIF image")?;
+
+        Ok(Avatar {
+            format,
+            data_uri: format!(
+                "data:{};base64,{}",
+                format.mime_type(),
+                STANDARD.encode(bytes)
+            ),
+        })
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> WebhookResult<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    pub fn data_uri(&self) -> &str {
+        &self.data_uri
+    }
+}
+
+impl fmt::Display for Avatar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.data_uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Avatar, ImageFormat};
+
+    #[test]
+    fn formats_are_detected() {
+        let avatar = Avatar::from_bytes(b"GIF89a\x01\x00").unwrap();
+        assert_eq!(avatar.format(), ImageFormat::Gif);
+        assert_eq!(avatar.data_uri(), "data:image/gif;base64,R0lGODlhAQA=");
+
+        assert_eq!(
+            ImageFormat::detect(b"\x89PNG\r\n\x1a\n..."),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::detect(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageFormat::Jpeg)
+        );
+        assert!(Avatar::from_bytes(b"<svg></svg>").is_err());
+    }
+
+    #[test]
+    fn oversized_images_are_rejected() {
+        let mut bytes = b"GIF89a".to_vec();
+        bytes.resize(Avatar::MAX_LEN + 1, 0);
+        assert!(Avatar::from_bytes(&bytes).is_err());
+    }
+}
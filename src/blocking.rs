@@ -0,0 +1,212 @@
+//! A blocking client for applications without an async runtime, like CLIs and build scripts.
+//!
+//! Enabled by the `blocking` feature. The requests are executed by `ureq`.
+
+use std::io::Read;
+
+use crate::models::{Message, Webhook};
+use crate::request::{build_request, webhook_url_error};
+use crate::WebhookResult;
+
+/// A blocking Client that sends webhooks for discord.
+pub struct WebhookClient {
+    agent: ureq::Agent,
+    url: String,
+}
+
+impl WebhookClient {
+    pub fn new(url: &str) -> Self {
+        Self::with_agent(url, ureq::Agent::new())
+    }
+
+    /// Creates a client executing its requests with `agent`, e.g. one configured with
+    /// timeouts or a proxy through `ureq::AgentBuilder`.
+    pub fn with_agent(url: &str, agent: ureq::Agent) -> Self {
+        Self {
+            agent,
+            url: url.to_owned(),
+        }
+    }
+
+    /// Example
+    /// ```no_run
+    /// # fn main() -> webhook::WebhookResult<()> {
+    /// let client = webhook::blocking::WebhookClient::new("URL");
+    /// client.send(|message| message
+    ///     .content("content")
+    ///     .username("username"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn send<Func>(&self, function: Func) -> WebhookResult<bool>
+    where
+        Func: FnOnce(&mut Message) -> &mut Message,
+    {
+        let mut message = Message::new();
+        function(&mut message);
+        self.send_message(&message)
+    }
+
+    /// Validates and sends `message`.
+    pub fn send_message(&self, message: &Message) -> WebhookResult<bool> {
+        let request = build_request(&self.url, message)?;
+        let (parts, body) = request.into_parts();
+
+        let mut outgoing = self.agent.request(parts.method.as_str(), &self.url);
+        for (name, value) in parts.headers.iter() {
+            outgoing = outgoing.set(name.as_str(), value.to_str()?);
+        }
+
+        // https://discord.com/developers/docs/resources/webhook#execute-webhook
+        // execute webhook returns either NO_CONTENT or, with `wait=true`, the message;
+        // ureq reports every status from 400 on as an error
+        match outgoing.send_bytes(&body) {
+            Ok(response) if (200..300).contains(&response.status()) => Ok(true),
+            Ok(response) | Err(ureq::Error::Status(_, response)) => Err(Box::new(
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, read_body(response)?),
+            )),
+            Err(err) => Err(Box::new(err)),
+        }
+    }
+
+    /// Fetches the webhook. Fails with `request::InvalidToken` or `request::NotFound` if
+    /// Discord does not accept the URL.
+    pub fn get_information(&self) -> WebhookResult<Webhook> {
+        let response = match self.agent.get(&self.url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(
+                    match webhook_url_error(http::StatusCode::from_u16(status)?) {
+                        Some(error) => error,
+                        None => Box::new(std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            read_body(response)?,
+                        )),
+                    },
+                );
+            }
+            Err(err) => return Err(Box::new(err)),
+        };
+        let webhook = serde_json::from_reader(response.into_reader())?;
+
+        Ok(webhook)
+    }
+}
+
+fn read_body(response: ureq::Response) -> WebhookResult<String> {
+    let mut body = String::new();
+    response.into_reader().read_to_string(&mut body)?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebhookClient;
+    use crate::request::InvalidToken;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    const URL: &str = "https://discord.com/api/webhooks/0/token";
+
+    /// Answers the requests of an agent with canned responses instead of sending them.
+    #[derive(Clone, Default)]
+    struct MockAgent {
+        responses: Arc<Mutex<VecDeque<(u16, &'static str)>>>,
+        requests: Arc<Mutex<Vec<Recorded>>>,
+    }
+
+    /// The method, URL and content type of a request.
+    type Recorded = (String, String, Option<String>);
+
+    impl MockAgent {
+        fn respond(&self, status: u16, body: &'static str) -> &Self {
+            self.responses.lock().unwrap().push_back((status, body));
+            self
+        }
+
+        fn client(&self, url: &str) -> WebhookClient {
+            let agent = ureq::AgentBuilder::new().middleware(self.clone()).build();
+            WebhookClient::with_agent(url, agent)
+        }
+    }
+
+    impl ureq::Middleware for MockAgent {
+        fn handle(
+            &self,
+            request: ureq::Request,
+            _next: ureq::MiddlewareNext,
+        ) -> Result<ureq::Response, ureq::Error> {
+            self.requests.lock().unwrap().push((
+                request.method().to_owned(),
+                request.url().to_owned(),
+                request.header("content-type").map(str::to_owned),
+            ));
+            let (status, body) = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or((204, ""));
+            ureq::Response::new(status, "", body)
+        }
+    }
+
+    #[test]
+    fn messages_are_posted_to_the_webhook() {
+        let mock = MockAgent::default();
+        let client = mock.client(URL);
+
+        assert!(client.send(|message| message.content("hello")).unwrap());
+
+        let requests = mock.requests.lock().unwrap();
+        assert_eq!(
+            *requests,
+            vec![(
+                "POST".to_owned(),
+                URL.to_owned(),
+                Some("application/json".to_owned())
+            )]
+        );
+    }
+
+    #[test]
+    fn a_returned_message_is_a_success() {
+        let mock = MockAgent::default();
+        mock.respond(200, r#"{"id": "5", "content": "hello"}"#);
+        let client = mock.client(&format!("{}?wait=true", URL));
+
+        assert!(client.send(|message| message.content("hello")).unwrap());
+    }
+
+    #[test]
+    fn errors_carry_the_response_body() {
+        let mock = MockAgent::default();
+        mock.respond(400, r#"{"message": "Cannot send an empty message"}"#);
+
+        let err = mock
+            .client(URL)
+            .send(|message| message.content("hello"))
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Cannot send an empty message"));
+    }
+
+    #[test]
+    fn webhooks_are_fetched() {
+        let mock = MockAgent::default();
+        mock.respond(
+            200,
+            r#"{"id": "0", "type": 1, "name": "Alerts", "channel_id": "1", "token": "token"}"#,
+        )
+        .respond(401, r#"{"message": "Invalid Webhook Token"}"#);
+        let client = mock.client(URL);
+
+        let webhook = client.get_information().unwrap();
+        assert_eq!(webhook.name.as_deref(), Some("Alerts"));
+        assert_eq!(webhook.token.as_deref(), Some("token"));
+
+        let err = client.get_information().unwrap_err();
+        assert!(err.downcast_ref::<InvalidToken>().is_some());
+        assert_eq!(mock.requests.lock().unwrap()[1].0, "GET");
+    }
+}
@@ -0,0 +1,204 @@
+use http::StatusCode;
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::transport::HttpResponse;
+use crate::WebhookResult;
+
+/// Fails requests fast after repeated failures instead of sending them to a webhook
+/// that is gone or to an API that is down.
+///
+/// The breaker opens after `failure_threshold` consecutive failures: no response, a server
+/// error, `401 Unauthorized` or `404 Not Found`. While it is open, requests fail with
+/// `CircuitOpen` without being sent. Once the cooldown has passed, one trial request is let
+/// through (half-open): if it succeeds the breaker closes, otherwise it opens again.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    trial: bool,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Fails if the breaker is open, lets the request through otherwise.
+    pub(crate) fn check(&self) -> Result<(), CircuitOpen> {
+        self.check_at(Instant::now())
+    }
+
+    /// Records the outcome of a request that was let through.
+    pub(crate) fn record(&self, result: &WebhookResult<HttpResponse>) {
+        self.record_at(!is_failure(result), Instant::now());
+    }
+
+    fn check_at(&self, now: Instant) -> Result<(), CircuitOpen> {
+        let mut state = self.state.lock().unwrap();
+        match state.open_until {
+            Some(until) if now < until => Err(CircuitOpen {
+                retry_in: until - now,
+            }),
+            Some(_) => {
+                // half-open: the trial request keeps the others out for another cooldown,
+                // so a trial that never completes does not block the breaker for good
+                state.open_until = Some(now + self.cooldown);
+                state.trial = true;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn record_at(&self, success: bool, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if success {
+            *state = State::default();
+            return;
+        }
+
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.trial || state.consecutive_failures >= self.failure_threshold {
+            state.open_until = Some(now + self.cooldown);
+            state.trial = false;
+        }
+    }
+}
+
+fn is_failure(result: &WebhookResult<HttpResponse>) -> bool {
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            status.is_server_error()
+                || status == StatusCode::UNAUTHORIZED
+                || status == StatusCode::NOT_FOUND
+        }
+        Err(_) => true,
+    }
+}
+
+/// A request that was not sent because the client's circuit breaker is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitOpen {
+    retry_in: Duration,
+}
+
+impl CircuitOpen {
+    /// How long until the breaker lets a trial request through.
+    pub fn retry_in(&self) -> Duration {
+        self.retry_in
+    }
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "circuit breaker is open after repeated failures, retrying in {:?}",
+            self.retry_in
+        )
+    }
+}
+
+impl Error for CircuitOpen {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircuitBreaker, CircuitOpen};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(10));
+        let now = Instant::now();
+
+        breaker.record_at(false, now);
+        breaker.record_at(false, now);
+        breaker.record_at(true, now);
+        breaker.record_at(false, now);
+        breaker.record_at(false, now);
+        assert!(breaker.check_at(now).is_ok());
+
+        breaker.record_at(false, now);
+        assert_eq!(
+            breaker.check_at(now + Duration::from_secs(4)),
+            Err(CircuitOpen {
+                retry_in: Duration::from_secs(6)
+            })
+        );
+    }
+
+    #[test]
+    fn half_open_trial_decides() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+        let now = Instant::now();
+        breaker.record_at(false, now);
+
+        // the trial fails: the breaker opens again
+        let later = now + Duration::from_secs(10);
+        assert!(breaker.check_at(later).is_ok());
+        assert!(breaker.check_at(later).is_err());
+        breaker.record_at(false, later);
+        assert!(breaker.check_at(later + Duration::from_secs(9)).is_err());
+
+        // the trial succeeds: the breaker closes
+        let later = later + Duration::from_secs(10);
+        assert!(breaker.check_at(later).is_ok());
+        breaker.record_at(true, later);
+        assert!(breaker.check_at(later).is_ok());
+        assert!(breaker.check_at(later).is_ok());
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn open_breaker_fails_without_sending() {
+        use crate::client::WebhookClient;
+        use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+        use crate::WebhookResult;
+        use futures_util::future::BoxFuture;
+        use std::sync::{Arc, Mutex};
+
+        struct NotFound(Arc<Mutex<usize>>);
+
+        impl WebhookTransport for NotFound {
+            fn execute(&self, _: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+                *self.0.lock().unwrap() += 1;
+                Box::pin(async { Ok(http::Response::builder().status(404).body(vec![])?) })
+            }
+        }
+
+        let requests = Arc::new(Mutex::new(0));
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(NotFound(requests.clone()))
+            .circuit_breaker(2, Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        for _ in 0..4 {
+            assert!(client
+                .send(|message| message.content("content"))
+                .await
+                .is_err());
+        }
+        let err = client
+            .send(|message| message.content("content"))
+            .await
+            .unwrap_err();
+        assert!(err.is::<CircuitOpen>());
+        assert_eq!(*requests.lock().unwrap(), 2);
+    }
+}
@@ -0,0 +1,220 @@
+use futures_util::stream::{self, StreamExt};
+use std::error::Error;
+
+use crate::client::{PreparedMessage, WebhookClient, WebhookResult};
+use crate::models::Message;
+use crate::request::validate;
+
+/// Sends the same message to several webhooks concurrently.
+///
+/// Every target is sent to even if others fail; the `BroadcastReport` tells which ones did.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # async fn run() -> WebhookResult<()> {
+/// let mut broadcaster = WebhookBroadcaster::new();
+/// broadcaster
+///     .add("URL_1")
+///     .add("URL_2")
+///     .max_concurrent_sends(4);
+/// let report = broadcaster.send(|message| message.content("deploy finished")).await?;
+/// for (target, err) in report.failures() {
+///     eprintln!("webhook {} failed: {}", target, err);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct WebhookBroadcaster {
+    clients: Vec<WebhookClient>,
+    max_concurrent_sends: Option<usize>,
+}
+
+impl WebhookBroadcaster {
+    pub fn new() -> Self {
+        Self {
+            clients: vec![],
+            max_concurrent_sends: None,
+        }
+    }
+
+    /// Adds a target with a default client for `url`.
+    pub fn add(&mut self, url: &str) -> &mut Self {
+        self.add_client(WebhookClient::new(url))
+    }
+
+    /// Adds a target with a custom client, e.g. one with its own transport or retry policy.
+    pub fn add_client(&mut self, client: WebhookClient) -> &mut Self {
+        self.clients.push(client);
+        self
+    }
+
+    /// Maximum number of targets sent to at the same time. All at once by default.
+    pub fn max_concurrent_sends(&mut self, max_concurrent_sends: usize) -> &mut Self {
+        self.max_concurrent_sends = Some(max_concurrent_sends.max(1));
+        self
+    }
+
+    /// The number of targets.
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Builds and validates the message once, then sends it to every target.
+    pub async fn send<Func>(&self, function: Func) -> WebhookResult<BroadcastReport>
+    where
+        Func: FnOnce(&mut Message) -> &mut Message,
+    {
+        let mut message = Message::new();
+        function(&mut message);
+        validate(&message)?;
+
+        Ok(self.send_message(&message).await)
+    }
+
+    pub async fn send_message(&self, message: &Message) -> BroadcastReport {
+        let limit = self
+            .max_concurrent_sends
+            .unwrap_or(self.clients.len())
+            .max(1);
+        let results = stream::iter(self.clients.iter())
+            .map(|client| client.send_message(message))
+            .buffered(limit)
+            .collect()
+            .await;
+
+        BroadcastReport { results }
+    }
+
+    /// Sends a message serialized once by `WebhookClient::prepare` to every target. It is sent
+    /// as prepared: the validation mode and redaction rules of the targets do not apply.
+    pub async fn send_prepared(&self, prepared: &PreparedMessage) -> BroadcastReport {
+        let limit = self
+            .max_concurrent_sends
+            .unwrap_or(self.clients.len())
+            .max(1);
+        let results = stream::iter(self.clients.iter())
+            .map(|client| client.send_prepared(prepared))
+            .buffered(limit)
+            .collect()
+            .await;
+
+        BroadcastReport { results }
+    }
+}
+
+/// The outcome of a broadcast, one result per target in the order the targets were added.
+#[derive(Debug)]
+pub struct BroadcastReport {
+    results: Vec<WebhookResult<bool>>,
+}
+
+impl BroadcastReport {
+    pub fn results(&self) -> &[WebhookResult<bool>] {
+        &self.results
+    }
+
+    /// Whether the message reached every target.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(Result::is_ok)
+    }
+
+    /// The index of every target that failed, with its error.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &(dyn Error + Send + Sync))> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(target, result)| result.as_ref().err().map(|err| (target, &**err)))
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::WebhookBroadcaster;
+    use crate::client::WebhookClient;
+    use crate::models::IntoMessage;
+    use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+    use crate::WebhookResult;
+    use futures_util::future::BoxFuture;
+    use std::sync::{Arc, Mutex};
+
+    /// Answers every request with `status` and counts them.
+    struct StatusTransport {
+        status: u16,
+        requests: Arc<Mutex<usize>>,
+    }
+
+    impl WebhookTransport for StatusTransport {
+        fn execute(&self, _: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+            *self.requests.lock().unwrap() += 1;
+            let status = self.status;
+            Box::pin(async move { Ok(http::Response::builder().status(status).body(vec![])?) })
+        }
+    }
+
+    fn client(status: u16, requests: &Arc<Mutex<usize>>) -> WebhookClient {
+        WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(StatusTransport {
+                status,
+                requests: requests.clone(),
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn every_target_is_reported() {
+        let requests = Arc::new(Mutex::new(0));
+        let mut broadcaster = WebhookBroadcaster::new();
+        broadcaster
+            .add_client(client(204, &requests))
+            .add_client(client(404, &requests))
+            .add_client(client(204, &requests))
+            .max_concurrent_sends(2);
+
+        let report = broadcaster
+            .send(|message| message.content("content"))
+            .await
+            .unwrap();
+        assert!(!report.is_success());
+        assert_eq!(report.results().len(), 3);
+        let failed: Vec<_> = report.failures().map(|(target, _)| target).collect();
+        assert_eq!(failed, vec![1]);
+        assert_eq!(*requests.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn prepared_messages_reach_every_target() {
+        let requests = Arc::new(Mutex::new(0));
+        let mut broadcaster = WebhookBroadcaster::new();
+        broadcaster
+            .add_client(client(204, &requests))
+            .add_client(client(204, &requests));
+
+        let prepared = client(204, &requests)
+            .prepare("content".into_message())
+            .await
+            .unwrap();
+        let report = broadcaster.send_prepared(&prepared).await;
+        assert!(report.is_success());
+        assert_eq!(*requests.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn invalid_messages_are_not_sent() {
+        let requests = Arc::new(Mutex::new(0));
+        let mut broadcaster = WebhookBroadcaster::new();
+        broadcaster.add_client(client(204, &requests));
+
+        assert!(broadcaster
+            .send(|message| message.content("a".repeat(2001)))
+            .await
+            .is_err());
+        assert_eq!(*requests.lock().unwrap(), 0);
+    }
+}
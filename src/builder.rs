@@ -0,0 +1,187 @@
+//! Owned builders, for building messages and embeds in expression position.
+//!
+//! The `&mut self` builders of `Message` and `Embed` need a binding to build into. The owned
+//! builders here take and return `self` instead, so a value can be built in one expression,
+//! and a partially built builder can be cloned and finished in different ways.
+//!
+//! ```no_run
+//! # use webhook::prelude::*;
+//! # const AVATAR: &str = "https://example.com/ci.png";
+//! # async fn run(client: WebhookClient) -> WebhookResult<()> {
+//! let embed = Embed::builder().title("Deployed").color(Color::GREEN).build();
+//!
+//! let base = Message::builder().username("ci").avatar_url(AVATAR);
+//! client.send_message(&base.clone().content("Build started").build()).await?;
+//! client.send_message(&base.content("Build finished").add_embed(embed).build()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::models::{
+    ActionRow, AllowedMentions, Attachment, Color, Container, Embed, IntoEmbed, IntoMessage,
+    MediaGallery, Message, Section, Separator, Timestamp,
+};
+
+/// Generates owned builder methods that forward to the `&mut self` builder of the value.
+macro_rules! owned_builders {
+    ($($(#[$attr:meta])* fn $name:ident($($arg:ident: $type:ty),*);)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(mut self, $($arg: $type),*) -> Self {
+                self.0.$name($($arg),*);
+                self
+            }
+        )*
+    };
+}
+
+/// An owned builder for a `Message`, see the module documentation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageBuilder(Message);
+
+impl MessageBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    owned_builders! {
+        fn content(content: impl Into<String>);
+        fn username(username: impl Into<String>);
+        fn avatar_url(avatar_url: impl Into<String>);
+        fn tts(tts: bool);
+        fn embed(func: impl FnOnce(&mut Embed) -> &mut Embed);
+        fn add_embed(embed: impl IntoEmbed);
+        fn action_row(func: impl FnOnce(&mut ActionRow) -> &mut ActionRow);
+        fn flags(flags: u64);
+        fn container(func: impl FnOnce(&mut Container) -> &mut Container);
+        fn text_display(content: impl Into<String>);
+        fn section(func: impl FnOnce(&mut Section) -> &mut Section);
+        fn media_gallery(func: impl FnOnce(&mut MediaGallery) -> &mut MediaGallery);
+        fn separator(func: impl FnOnce(&mut Separator) -> &mut Separator);
+        fn extra(key: impl Into<String>, value: serde_json::Value);
+        fn allowed_mentions(func: impl FnOnce(&mut AllowedMentions) -> &mut AllowedMentions);
+        fn attachment(filename: impl Into<String>, data: Vec<u8>, func: impl FnOnce(&mut Attachment) -> &mut Attachment);
+        fn attachment_from_url(url: impl Into<String>, func: impl FnOnce(&mut Attachment) -> &mut Attachment);
+        fn upload_limit(upload_limit: u64);
+        fn suppress_mentions();
+    }
+
+    /// Applies any `&mut self` builder of `Message`, for the ones not mirrored here.
+    pub fn with<Func>(mut self, func: Func) -> Self
+    where
+        Func: FnOnce(&mut Message) -> &mut Message,
+    {
+        func(&mut self.0);
+        self
+    }
+
+    pub fn build(self) -> Message {
+        self.0
+    }
+}
+
+impl From<Message> for MessageBuilder {
+    fn from(message: Message) -> Self {
+        Self(message)
+    }
+}
+
+impl IntoMessage for MessageBuilder {
+    fn into_message(self) -> Message {
+        self.build()
+    }
+}
+
+/// An owned builder for an `Embed`, see the module documentation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbedBuilder(Embed);
+
+impl EmbedBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    owned_builders! {
+        fn title(title: impl Into<String>);
+        fn description(description: impl Into<String>);
+        fn url(url: impl Into<String>);
+        fn timestamp(timestamp: impl Into<Timestamp>);
+        fn timestamp_now();
+        fn color(color: impl Into<Color>);
+        fn footer(text: impl Into<String>, icon_url: impl Into<Option<String>>);
+        fn image(url: impl Into<String>);
+        fn video(url: impl Into<String>);
+        fn thumbnail(url: impl Into<String>);
+        fn provider(name: impl Into<String>, url: impl Into<String>);
+        fn author(name: impl Into<String>, url: impl Into<Option<String>>, icon_url: impl Into<Option<String>>);
+        fn field(name: impl Into<String>, value: impl Into<String>, inline: bool);
+    }
+
+    /// Applies any `&mut self` builder of `Embed`, for the ones not mirrored here.
+    pub fn with<Func>(mut self, func: Func) -> Self
+    where
+        Func: FnOnce(&mut Embed) -> &mut Embed,
+    {
+        func(&mut self.0);
+        self
+    }
+
+    pub fn build(self) -> Embed {
+        self.0
+    }
+}
+
+impl From<Embed> for EmbedBuilder {
+    fn from(embed: Embed) -> Self {
+        Self(embed)
+    }
+}
+
+impl IntoEmbed for EmbedBuilder {
+    fn into_embed(self) -> Embed {
+        self.build()
+    }
+}
+
+impl Message {
+    /// Starts an owned `MessageBuilder`.
+    pub fn builder() -> MessageBuilder {
+        MessageBuilder::new()
+    }
+}
+
+impl Embed {
+    /// Starts an owned `EmbedBuilder`.
+    pub fn builder() -> EmbedBuilder {
+        EmbedBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{Color, Embed, Message};
+
+    #[test]
+    fn values_are_built_in_expression_position() {
+        let embed = Embed::builder()
+            .title("Deployed")
+            .color(Color::GREEN)
+            .field("Service", "billing", true)
+            .build();
+        assert_eq!(embed.title.as_deref(), Some("Deployed"));
+
+        let base = Message::builder().username("ci");
+        let started = base.clone().content("Build started").build();
+        let finished = base
+            .content("Build finished")
+            .add_embed(embed)
+            .with(|message| message.tts(true))
+            .build();
+        assert_eq!(started.content.as_deref(), Some("Build started"));
+        assert!(started.embeds.is_empty());
+        assert_eq!(finished.username.as_deref(), Some("ci"));
+        assert_eq!(finished.embeds[0].fields[0].value, "billing");
+        assert!(finished.tts);
+        assert!(finished.validate().is_ok());
+    }
+}
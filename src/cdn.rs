@@ -0,0 +1,190 @@
+//! URLs of images on the Discord CDN, built from the hashes the API returns, e.g. the avatar of
+//! the `Webhook` returned by `WebhookClient::get_information`.
+//!
+//! Example
+//! ```no_run
+//! # use webhook::cdn::{self, CdnImage};
+//! # use webhook::prelude::*;
+//! # async fn run(client: WebhookClient) -> WebhookResult<()> {
+//! let webhook = client.get_information().await?;
+//! let avatar = cdn::webhook_avatar_url(&webhook, CdnImage::new().size(128));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::models::{Snowflake, Webhook};
+
+const CDN_BASE: &str = "https://cdn.discordapp.com";
+
+/// The file formats the CDN serves images in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CdnFormat {
+    Png,
+    Jpeg,
+    WebP,
+    /// Only available for animated images, whose hash starts with `a_`; other images are
+    /// served as PNG instead.
+    Gif,
+}
+
+impl CdnFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CdnFormat::Png => "png",
+            CdnFormat::Jpeg => "jpg",
+            CdnFormat::WebP => "webp",
+            CdnFormat::Gif => "gif",
+        }
+    }
+}
+
+/// The format and size an image is requested in.
+///
+/// Without a format, animated images are served as GIF and others as PNG; without a size, the
+/// image is served in its original size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CdnImage {
+    format: Option<CdnFormat>,
+    size: Option<u16>,
+}
+
+impl CdnImage {
+    pub const MIN_SIZE: u16 = 16;
+    pub const MAX_SIZE: u16 = 4096;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn format(&mut self, format: CdnFormat) -> &mut Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// The edge length in pixels. The CDN only serves powers of two between `MIN_SIZE` and
+    /// `MAX_SIZE`, so other sizes are rounded up to the next one.
+    pub fn size(&mut self, size: u16) -> &mut Self {
+        self.size = Some(
+            size.clamp(Self::MIN_SIZE, Self::MAX_SIZE)
+                .next_power_of_two(),
+        );
+        self
+    }
+
+    /// The URL of the image at `path` with the hash `hash`, e.g. `avatars/{id}`.
+    fn url(&self, path: fmt::Arguments<'_>, hash: &str) -> String {
+        let animated = hash.starts_with("a_");
+        let format = match self.format {
+            Some(CdnFormat::Gif) if !animated => CdnFormat::Png,
+            Some(format) => format,
+            None if animated => CdnFormat::Gif,
+            None => CdnFormat::Png,
+        };
+        let mut url = format!("{}/{}/{}.{}", CDN_BASE, path, hash, format.extension());
+        if let Some(size) = self.size {
+            url.push_str(&format!("?size={}", size));
+        }
+        url
+    }
+}
+
+/// The avatar of a user with the avatar hash `hash`.
+pub fn user_avatar_url(user_id: Snowflake, hash: &str, image: &CdnImage) -> String {
+    image.url(format_args!("avatars/{}", user_id.get()), hash)
+}
+
+/// The avatar of `webhook`, or the default avatar Discord shows for it if it has none.
+pub fn webhook_avatar_url(webhook: &Webhook, image: &CdnImage) -> String {
+    match webhook.avatar.as_deref() {
+        Some(hash) => image.url(format_args!("avatars/{}", webhook.id.get()), hash),
+        None => default_avatar_url(default_avatar_index(webhook.id)),
+    }
+}
+
+/// The icon of a guild with the icon hash `hash`, e.g. of `Webhook::source_guild`.
+pub fn guild_icon_url(guild_id: Snowflake, hash: &str, image: &CdnImage) -> String {
+    image.url(format_args!("icons/{}", guild_id.get()), hash)
+}
+
+/// The number of default avatars.
+pub const DEFAULT_AVATAR_COUNT: u8 = 6;
+
+/// The default avatar of a user or webhook without an avatar, derived from its id.
+pub fn default_avatar_index(id: Snowflake) -> u8 {
+    ((id.get() >> 22) % DEFAULT_AVATAR_COUNT as u64) as u8
+}
+
+/// The default avatar of a user who still has a discriminator (the `#1234` after legacy
+/// usernames).
+pub fn legacy_default_avatar_index(discriminator: u16) -> u8 {
+    (discriminator % 5) as u8
+}
+
+/// The default avatar with the index `index`, see `default_avatar_index`. Default avatars are
+/// only served as PNG.
+pub fn default_avatar_url(index: u8) -> String {
+    format!(
+        "{}/embed/avatars/{}.png",
+        CDN_BASE,
+        index % DEFAULT_AVATAR_COUNT
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        default_avatar_index, default_avatar_url, guild_icon_url, legacy_default_avatar_index,
+        user_avatar_url, webhook_avatar_url, CdnFormat, CdnImage,
+    };
+    use crate::models::{Snowflake, Webhook};
+
+    #[test]
+    fn image_urls_are_built() {
+        let id = Snowflake::new(80351110224678912);
+        assert_eq!(
+            user_avatar_url(id, "8342729096ea3675442027381ff50dfe", &CdnImage::new()),
+            "https://cdn.discordapp.com/avatars/80351110224678912/8342729096ea3675442027381ff50dfe.png"
+        );
+        assert_eq!(
+            user_avatar_url(id, "a_1f2e", CdnImage::new().size(100)),
+            "https://cdn.discordapp.com/avatars/80351110224678912/a_1f2e.gif?size=128"
+        );
+        assert_eq!(
+            guild_icon_url(id, "1f2e", CdnImage::new().format(CdnFormat::Gif).size(8)),
+            "https://cdn.discordapp.com/icons/80351110224678912/1f2e.png?size=16"
+        );
+        assert_eq!(
+            guild_icon_url(
+                id,
+                "a_1f2e",
+                CdnImage::new().format(CdnFormat::WebP).size(9000)
+            ),
+            "https://cdn.discordapp.com/icons/80351110224678912/a_1f2e.webp?size=4096"
+        );
+    }
+
+    #[test]
+    fn default_avatars_are_derived() {
+        let id = Snowflake::new(80351110224678912);
+        assert_eq!(
+            default_avatar_index(id),
+            ((80351110224678912u64 >> 22) % 6) as u8
+        );
+        assert_eq!(legacy_default_avatar_index(1337), 2);
+        assert_eq!(
+            default_avatar_url(3),
+            "https://cdn.discordapp.com/embed/avatars/3.png"
+        );
+
+        let webhook: Webhook = serde_json::from_str(
+            r#"{"id":"80351110224678912","type":1,"channel_id":"3","name":null,"avatar":null,"application_id":null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            webhook_avatar_url(&webhook, &CdnImage::new()),
+            default_avatar_url(default_avatar_index(id))
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Helpers for Discord markup: escaping user supplied text and formatting mentions and timestamps.
+
+use crate::models::Snowflake;
+
+/// Escapes the characters Discord interprets as markdown, so `text` is shown verbatim.
+///
+/// Headings, block quotes and list markers are only escaped at the start of a line.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut line_start = true;
+    for c in text.chars() {
+        match c {
+            '\\' | '*' | '_' | '~' | '`' | '|' => escaped.push('\\'),
+            '#' | '>' | '-' if line_start => escaped.push('\\'),
+            _ => {}
+        }
+        escaped.push(c);
+        line_start = c == '\n' || (line_start && c == ' ');
+    }
+    escaped
+}
+
+/// Breaks `@everyone`, `@here` and user, role and channel mentions in `text` by inserting a zero
+/// width space, so they neither ping nor render as mentions.
+///
+/// To keep mentions from pinging while still rendering them, use
+/// `Message::suppress_mentions` instead.
+pub fn escape_mentions(text: &str) -> String {
+    text.replace('@', "@\u{200B}").replace("<#", "<#\u{200B}")
+}
+
+/// `<@id>`, mentioning a user.
+pub fn user_mention<Id: Into<Snowflake>>(id: Id) -> String {
+    format!("<@{}>", id.into())
+}
+
+/// `<@&id>`, mentioning a role.
+pub fn role_mention<Id: Into<Snowflake>>(id: Id) -> String {
+    format!("<@&{}>", id.into())
+}
+
+/// `<#id>`, linking a channel.
+pub fn channel_mention<Id: Into<Snowflake>>(id: Id) -> String {
+    format!("<#{}>", id.into())
+}
+
+/// How a timestamp is rendered. Discord renders it in the timezone and locale of the reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampStyle {
+    /// `16:20`
+    ShortTime,
+    /// `16:20:30`
+    LongTime,
+    /// `20/04/2021`
+    ShortDate,
+    /// `20 April 2021`
+    LongDate,
+    /// `20 April 2021 16:20`
+    ShortDateTime,
+    /// `Tuesday, 20 April 2021 16:20`
+    LongDateTime,
+    /// `2 months ago`, `in 5 minutes`
+    Relative,
+}
+
+impl TimestampStyle {
+    fn flag(self) -> char {
+        match self {
+            TimestampStyle::ShortTime => 't',
+            TimestampStyle::LongTime => 'T',
+            TimestampStyle::ShortDate => 'd',
+            TimestampStyle::LongDate => 'D',
+            TimestampStyle::ShortDateTime => 'f',
+            TimestampStyle::LongDateTime => 'F',
+            TimestampStyle::Relative => 'R',
+        }
+    }
+}
+
+/// `<t:unix_seconds:style>`, a timestamp rendered for every reader in their own timezone.
+pub fn timestamp(unix_seconds: i64, style: TimestampStyle) -> String {
+    format!("<t:{}:{}>", unix_seconds, style.flag())
+}
+
+/// `<t:unix_seconds:R>`, e.g. "in 5 minutes" or "2 hours ago".
+pub fn relative_timestamp(unix_seconds: i64) -> String {
+    timestamp(unix_seconds, TimestampStyle::Relative)
+}
+
+/// Shortens `text` to at most `max_chars` characters, ending it with `…` if it was cut.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_owned();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    if max_chars > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// Wraps `text` in a code block with syntax highlighting for `language` (may be empty), fitting
+/// the result into `max_chars` characters.
+///
+/// Backticks in `text` cannot close the block early. Text that does not fit is cut off and ends
+/// with `…`. Common limits are 1024 for embed field values, 2000 for message content and 4096 for
+/// embed descriptions.
+///
+/// Example
+/// ```no_run
+/// # use webhook::fmt;
+/// # use webhook::models::Embed;
+/// # let mut embed = Embed::new();
+/// # let backtrace = std::backtrace::Backtrace::capture().to_string();
+/// embed.field("Backtrace", &fmt::codeblock("rust", &backtrace, 1024), false);
+/// ```
+pub fn codeblock(language: &str, text: &str, max_chars: usize) -> String {
+    const ELLIPSIS: char = '…';
+
+    let mut body = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        if c == '`' && previous == Some('`') {
+            body.push('\u{200B}');
+        }
+        body.push(c);
+        previous = Some(c);
+    }
+    // a trailing backtick would merge with the closing fence
+    if previous == Some('`') {
+        body.push('\u{200B}');
+    }
+
+    let overhead = "```".len() * 2 + "\n".len() * 2 + language.chars().count();
+    let budget = max_chars.saturating_sub(overhead);
+    if body.chars().count() > budget {
+        body = body.chars().take(budget.saturating_sub(1)).collect();
+        if budget > 0 {
+            body.push(ELLIPSIS);
+        }
+    }
+
+    format!("```{}\n{}\n```", language, body)
+}
+
+/// Splits `text` into chunks of at most `max_chars` characters.
+///
+/// Chunks end at line breaks where possible, then at whitespace; only lines without any
+/// whitespace are cut mid-word. The separators at the cuts are dropped.
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = vec![];
+    let mut rest = text;
+
+    while rest.chars().count() > max_chars {
+        // the first character that does not fit, a separator there still allows a full chunk
+        let (limit, next) = rest
+            .char_indices()
+            .nth(max_chars)
+            .expect("rest is longer than max_chars");
+        let window = &rest[..limit + next.len_utf8()];
+        let cut = window
+            .rfind('\n')
+            .or_else(|| window.rfind(char::is_whitespace))
+            .filter(|&cut| cut > 0);
+
+        match cut {
+            Some(cut) => {
+                chunks.push(rest[..cut].to_owned());
+                let separator_len = rest[cut..].chars().next().map_or(0, char::len_utf8);
+                rest = &rest[cut + separator_len..];
+            }
+            None => {
+                chunks.push(rest[..limit].to_owned());
+                rest = &rest[limit..];
+            }
+        }
+    }
+
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest.to_owned());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_is_escaped() {
+        assert_eq!(
+            escape_markdown("**bold** a_b `x`"),
+            r"\*\*bold\*\* a\_b \`x\`"
+        );
+        assert_eq!(
+            escape_markdown("# title\n - item a-b"),
+            "\\# title\n \\- item a-b"
+        );
+        assert_eq!(escape_markdown(r"C:\path"), r"C:\\path");
+    }
+
+    #[test]
+    fn mentions_are_escaped() {
+        assert_eq!(escape_mentions("@everyone"), "@\u{200B}everyone");
+        assert_eq!(
+            escape_mentions("<@1> <@&2> <#3>"),
+            "<@\u{200B}1> <@\u{200B}&2> <#\u{200B}3>"
+        );
+    }
+
+    #[test]
+    fn codeblocks_are_escaped_and_truncated() {
+        assert_eq!(codeblock("rs", "let a = 1;", 100), "```rs\nlet a = 1;\n```");
+        assert_eq!(
+            codeblock("", "a```b`", 100),
+            "```\na`\u{200B}`\u{200B}`b`\u{200B}\n```"
+        );
+
+        let block = codeblock("txt", &"x".repeat(2000), 1024);
+        assert_eq!(block.chars().count(), 1024);
+        assert!(block.ends_with("x…\n```"));
+    }
+
+    #[test]
+    fn long_text_is_truncated() {
+        assert_eq!(truncate("abc", 3), "abc");
+        assert_eq!(truncate("abcd", 3), "ab…");
+        assert_eq!(truncate("ééé", 1), "…");
+        assert_eq!(truncate("abc", 0), "");
+    }
+
+    #[test]
+    fn text_is_split_at_line_breaks_then_whitespace() {
+        assert_eq!(split_text("aaa\nbb\ncc", 6), vec!["aaa\nbb", "cc"]);
+        assert_eq!(split_text("aa bb cc", 5), vec!["aa bb", "cc"]);
+        assert_eq!(split_text("ééééé", 2), vec!["éé", "éé", "é"]);
+        assert_eq!(split_text("short", 2000), vec!["short"]);
+        assert_eq!(split_text("", 2000), vec![""]);
+    }
+
+    #[test]
+    fn markup_is_formatted() {
+        assert_eq!(user_mention(1), "<@1>");
+        assert_eq!(role_mention(Snowflake(2)), "<@&2>");
+        assert_eq!(channel_mention(3), "<#3>");
+        assert_eq!(relative_timestamp(1_700_000_000), "<t:1700000000:R>");
+        assert_eq!(timestamp(0, TimestampStyle::LongDate), "<t:0:D>");
+    }
+}
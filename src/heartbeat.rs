@@ -0,0 +1,313 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::client::{WebhookClient, WebhookResult};
+use crate::fmt::relative_timestamp;
+use crate::models::{Color, Embed, EmbedField, Message, Snowflake};
+use crate::transport::sleep;
+
+/// A status message that is kept up to date on an interval, for dead man's switch style
+/// monitoring.
+///
+/// The first beat sends the message, later beats edit it. The embed shows the uptime, the
+/// time of the last `feed` and the custom fields, and turns red once the application has not
+/// fed the heartbeat for longer than `stale_after`. If the message cannot be edited a few
+/// times in a row (e.g. because it was deleted), the next beat sends a new one.
+///
+/// The handle is cheap to clone; feed it from the application and drive `run` on any
+/// runtime.
+///
+/// Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use webhook::heartbeat::Heartbeat;
+/// # use webhook::prelude::*;
+/// # const URL: &str = "https://discord.com/api/webhooks/0/token";
+/// # async fn process_batch() -> WebhookResult<usize> { Ok(0) }
+/// # async fn run() -> WebhookResult<()> {
+/// let heartbeat = Heartbeat::builder("billing-worker")
+///     .interval(Duration::from_secs(60))
+///     .stale_after(Duration::from_secs(300))
+///     .build(WebhookClient::new(URL));
+/// tokio::spawn({
+///     let heartbeat = heartbeat.clone();
+///     async move { heartbeat.run().await }
+/// });
+///
+/// loop {
+///     let processed = process_batch().await?;
+///     heartbeat.field("Processed", &processed.to_string());
+///     heartbeat.feed();
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Heartbeat {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client: WebhookClient,
+    name: String,
+    interval: Duration,
+    stale_after: Duration,
+    started_at: SystemTime,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    last_feed: Option<SystemTime>,
+    fields: Vec<(String, String)>,
+    message_id: Option<Snowflake>,
+    failed_edits: u32,
+}
+
+impl Heartbeat {
+    /// Failed edits after which a new message is sent instead.
+    const MAX_FAILED_EDITS: u32 = 3;
+
+    /// Creates a builder for a heartbeat titled `name`.
+    pub fn builder(name: &str) -> HeartbeatBuilder {
+        HeartbeatBuilder::new(name)
+    }
+
+    /// Marks the application as alive.
+    pub fn feed(&self) {
+        self.inner.state.lock().unwrap().last_feed = Some(SystemTime::now());
+    }
+
+    /// Shows `value` under `name` in the status embed, replacing an earlier value.
+    pub fn field(&self, name: &str, value: &str) {
+        let mut state = self.inner.state.lock().unwrap();
+        match state.fields.iter_mut().find(|(field, _)| field == name) {
+            Some((_, current)) => *current = value.to_owned(),
+            None => state.fields.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    pub fn remove_field(&self, name: &str) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.fields.retain(|(field, _)| field != name);
+    }
+
+    /// Beats on the interval until the future is dropped. Failed beats are retried on the
+    /// next interval.
+    pub async fn run(&self) {
+        loop {
+            let _ = self.beat().await;
+            sleep(self.inner.interval).await;
+        }
+    }
+
+    /// Sends or edits the status message once.
+    pub async fn beat(&self) -> WebhookResult<()> {
+        let (message, message_id) = {
+            let state = self.inner.state.lock().unwrap();
+            (
+                self.status_message(&state, SystemTime::now()),
+                state.message_id,
+            )
+        };
+
+        match message_id {
+            Some(message_id) => {
+                let result = self.inner.client.edit_message(message_id, &message).await;
+                let mut state = self.inner.state.lock().unwrap();
+                match result.as_ref() {
+                    Ok(()) => state.failed_edits = 0,
+                    Err(_) => {
+                        state.failed_edits += 1;
+                        if state.failed_edits >= Self::MAX_FAILED_EDITS {
+                            state.message_id = None;
+                            state.failed_edits = 0;
+                        }
+                    }
+                }
+                result
+            }
+            None => {
+                let message_id = self.inner.client.send_message_for_id(&message).await?;
+                self.inner.state.lock().unwrap().message_id = Some(message_id);
+                Ok(())
+            }
+        }
+    }
+
+    fn status_message(&self, state: &State, now: SystemTime) -> Message {
+        let inner = &self.inner;
+        let since_feed = now
+            .duration_since(state.last_feed.unwrap_or(inner.started_at))
+            .unwrap_or_default();
+        let uptime = now.duration_since(inner.started_at).unwrap_or_default();
+
+        let mut embed = Embed::new();
+        embed.title(&inner.name).timestamp_now();
+        if since_feed <= inner.stale_after {
+            embed.description("Alive").color(Color::GREEN);
+        } else {
+            embed
+                .description(format!("No heartbeat for {}", format_duration(since_feed)))
+                .color(Color::RED);
+        }
+
+        let last_feed = match state.last_feed {
+            Some(last_feed) => relative_timestamp(unix_seconds(last_feed)),
+            None => "never".to_owned(),
+        };
+        embed
+            .fields
+            .push(EmbedField::new("Uptime", format_duration(uptime), true));
+        embed
+            .fields
+            .push(EmbedField::new("Last success", &last_feed, true));
+        for (name, value) in state.fields.iter() {
+            embed.fields.push(EmbedField::new(name, value, true));
+        }
+
+        let mut message = Message::new();
+        message.embeds.push(embed);
+        message
+    }
+}
+
+/// Builder for a `Heartbeat`.
+pub struct HeartbeatBuilder {
+    name: String,
+    interval: Duration,
+    stale_after: Duration,
+}
+
+impl HeartbeatBuilder {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            interval: Duration::from_secs(60),
+            stale_after: Duration::from_secs(180),
+        }
+    }
+
+    /// How often the status message is updated. One minute by default.
+    pub fn interval(&mut self, interval: Duration) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    /// How long after the last `feed` the status turns red. Three minutes by default.
+    pub fn stale_after(&mut self, stale_after: Duration) -> &mut Self {
+        self.stale_after = stale_after;
+        self
+    }
+
+    pub fn build(&self, client: WebhookClient) -> Heartbeat {
+        Heartbeat {
+            inner: Arc::new(Inner {
+                client,
+                name: self.name.clone(),
+                interval: self.interval,
+                stale_after: self.stale_after,
+                started_at: SystemTime::now(),
+                state: Mutex::new(State::default()),
+            }),
+        }
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Formats a duration like `2d 3h 4m 5s`, leaving out leading zero units.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let units = [
+        (seconds / 86_400, "d"),
+        (seconds / 3_600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let parts: Vec<String> = units
+        .iter()
+        .skip_while(|(value, unit)| *value == 0 && *unit != "s")
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_duration;
+    use std::time::Duration;
+
+    #[test]
+    fn durations_are_formatted() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "0s");
+        assert_eq!(format_duration(Duration::from_secs(61)), "1m 1s");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 86_400 + 5)),
+            "2d 0h 0m 5s"
+        );
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn status_message_is_sent_then_edited() {
+        use super::Heartbeat;
+        use crate::client::WebhookClient;
+        use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+        use crate::WebhookResult;
+        use futures_util::future::BoxFuture;
+        use std::sync::{Arc, Mutex};
+
+        /// Records method, URI and embed of every request and answers like Discord.
+        struct FakeDiscord(Arc<Mutex<Vec<(String, String, serde_json::Value)>>>);
+
+        impl WebhookTransport for FakeDiscord {
+            fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+                let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+                self.0.lock().unwrap().push((
+                    request.method().to_string(),
+                    request.uri().to_string(),
+                    body["embeds"][0].clone(),
+                ));
+                Box::pin(async {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .body(br#"{"id": "123"}"#.to_vec())?)
+                })
+            }
+        }
+
+        let requests = Arc::new(Mutex::new(vec![]));
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(FakeDiscord(requests.clone()))
+            .build()
+            .unwrap();
+        let heartbeat = Heartbeat::builder("worker")
+            .stale_after(Duration::from_secs(60))
+            .build(client);
+
+        heartbeat.field("Processed", "10");
+        heartbeat.beat().await.unwrap();
+        heartbeat.feed();
+        heartbeat.field("Processed", "20");
+        heartbeat.beat().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].0, "POST");
+        assert_eq!(
+            requests[0].1,
+            "https://discord.com/api/webhooks/0/token?wait=true"
+        );
+        assert_eq!(requests[0].2["fields"][1]["value"], "never");
+        assert_eq!(requests[1].0, "PATCH");
+        assert_eq!(
+            requests[1].1,
+            "https://discord.com/api/webhooks/0/token/messages/123"
+        );
+        assert_eq!(requests[1].2["color"], 0x57F287);
+        assert_eq!(requests[1].2["fields"][2]["value"], "20");
+    }
+}
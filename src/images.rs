@@ -0,0 +1,184 @@
+//! Shrinking images so they fit the upload limit, enabled by the `image` feature.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageReader};
+use std::io::Cursor;
+
+use crate::models::Message;
+use crate::WebhookResult;
+
+/// The format oversized images are re-encoded to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Lossy, with a quality from 1 to 100. Transparency is lost.
+    Jpeg { quality: u8 },
+    /// Lossless.
+    WebP,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg { .. } => "jpg",
+            ImageFormat::WebP => "webp",
+        }
+    }
+}
+
+/// How `Attachment::fit_image` shrinks an image that is too large.
+///
+/// An image larger than `max_size` bytes or `max_dimensions` is scaled down to fit the
+/// dimensions and re-encoded, then scaled down further until it fits `max_size`. Images that
+/// already fit are left untouched.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # let mut message = Message::new();
+/// # let png = std::fs::read("screenshot.png").unwrap();
+/// let mut policy = ImagePolicy::new();
+/// policy.max_dimensions(1920, 1080).format(ImageFormat::Jpeg { quality: 80 });
+/// message.attachment("screenshot.png", png, |file| file.fit_image(&policy));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImagePolicy {
+    max_size: u64,
+    max_dimensions: Option<(u32, u32)>,
+    format: ImageFormat,
+}
+
+impl ImagePolicy {
+    /// Fits images into `Message::DEFAULT_UPLOAD_LIMIT` as JPEGs of quality 85.
+    pub fn new() -> Self {
+        Self {
+            max_size: Message::DEFAULT_UPLOAD_LIMIT,
+            max_dimensions: None,
+            format: ImageFormat::Jpeg { quality: 85 },
+        }
+    }
+
+    /// The maximum size of the encoded image in bytes.
+    pub fn max_size(&mut self, max_size: u64) -> &mut Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The maximum width and height; the aspect ratio is kept.
+    pub fn max_dimensions(&mut self, max_width: u32, max_height: u32) -> &mut Self {
+        self.max_dimensions = Some((max_width.max(1), max_height.max(1)));
+        self
+    }
+
+    pub fn format(&mut self, format: ImageFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// The re-encoded image and its file extension, or `None` if `data` already fits.
+    pub(crate) fn fit(&self, data: &[u8]) -> WebhookResult<Option<(Vec<u8>, &'static str)>> {
+        let (width, height) = ImageReader::new(Cursor::new(data))
+            .with_guessed_format()?
+            .into_dimensions()?;
+        let too_wide = self
+            .max_dimensions
+            .is_some_and(|(max_width, max_height)| width > max_width || height > max_height);
+        if !too_wide && data.len() as u64 <= self.max_size {
+            return Ok(None);
+        }
+
+        let mut image = image::load_from_memory(data)?;
+        if let Some((max_width, max_height)) = self.max_dimensions.filter(|_| too_wide) {
+            image = image.resize(max_width, max_height, FilterType::Triangle);
+        }
+        loop {
+            let encoded = self.encode(&image)?;
+            if encoded.len() as u64 <= self.max_size {
+                return Ok(Some((encoded, self.format.extension())));
+            }
+            let (width, height) = image.dimensions();
+            if width <= Self::MIN_DIMENSION && height <= Self::MIN_DIMENSION {
+                return Err("The image does not fit the maximum size even when scaled down".into());
+            }
+            image = image.resize(width * 3 / 4, height * 3 / 4, FilterType::Triangle);
+        }
+    }
+
+    fn encode(&self, image: &DynamicImage) -> WebhookResult<Vec<u8>> {
+        let mut encoded = Vec::new();
+        match self.format {
+            ImageFormat::Jpeg { quality } => {
+                let encoder = JpegEncoder::new_with_quality(&mut encoded, quality.clamp(1, 100));
+                DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)?;
+            }
+            ImageFormat::WebP => {
+                let encoder = WebPEncoder::new_lossless(&mut encoded);
+                DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(encoder)?;
+            }
+        }
+        Ok(encoded)
+    }
+
+    /// Images are not scaled down below this width and height.
+    const MIN_DIMENSION: u32 = 16;
+}
+
+impl Default for ImagePolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageFormat, ImagePolicy};
+    use crate::models::Message;
+    use image::{ImageFormat as Format, RgbImage};
+    use std::io::Cursor;
+
+    /// A PNG of noise, which compresses badly.
+    fn noise_png(width: u32, height: u32) -> Vec<u8> {
+        let mut seed: u32 = 7;
+        let image = RgbImage::from_fn(width, height, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [red, green, blue, _] = seed.to_be_bytes();
+            image::Rgb([red, green, blue])
+        });
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), Format::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn oversized_images_are_shrunk() {
+        let png = noise_png(400, 300);
+        let mut message = Message::new();
+        let mut policy = ImagePolicy::new();
+        policy.max_size(100_000).max_dimensions(200, 200);
+        message.attachment("screenshot.png", png.clone(), |file| {
+            file.fit_image(&policy)
+        });
+
+        let attachment = &message.attachments[0];
+        assert_eq!(attachment.filename, "screenshot.jpg");
+        assert!(attachment.size() <= 100_000);
+        let shrunk = image::load_from_memory(attachment.data.as_ref().unwrap()).unwrap();
+        assert_eq!((shrunk.width(), shrunk.height()), (200, 150));
+
+        policy.format(ImageFormat::WebP).max_size(png.len() as u64);
+        message.attachment("small.png", noise_png(20, 20), |file| {
+            file.fit_image(&policy)
+        });
+        assert_eq!(message.attachments[1].filename, "small.png");
+        message.attachment("notes.txt", b"not an image".to_vec(), |file| {
+            file.fit_image(&policy)
+        });
+        assert_eq!(
+            message.attachments[2].data.as_deref(),
+            Some(&b"not an image"[..])
+        );
+    }
+}
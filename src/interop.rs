@@ -0,0 +1,160 @@
+//! Conversions from the embed types of other Discord libraries, so embeds built for a bot
+//! can be sent through a webhook. Enabled by the `serenity-interop` and `twilight-interop`
+//! features.
+//!
+//! Both libraries serialize their embeds in Discord's format, which `Embed` reads back.
+//! Providers and media without the fields `Embed` requires, e.g. a provider without a URL,
+//! are left out; webhooks cannot set providers and videos anyway.
+
+use serde::Serialize;
+use std::convert::TryFrom;
+
+use crate::models::Embed;
+
+/// The parts of an embed that are left out unless they have all of the fields.
+const REQUIRED_FIELDS: [(&str, &[&str]); 4] = [
+    ("provider", &["name", "url"]),
+    ("video", &["url"]),
+    ("image", &["url"]),
+    ("thumbnail", &["url"]),
+];
+
+fn embed_from<Other: Serialize>(other: &Other) -> Result<Embed, serde_json::Error> {
+    let mut value = serde_json::to_value(other)?;
+    if let Some(object) = value.as_object_mut() {
+        for (part, fields) in REQUIRED_FIELDS.iter() {
+            let incomplete = object.get(*part).is_some_and(|part| {
+                fields
+                    .iter()
+                    .any(|field| part.get(field).is_none_or(|value| value.is_null()))
+            });
+            if incomplete {
+                object.remove(*part);
+            }
+        }
+    }
+    Embed::from_value(value)
+}
+
+#[cfg(feature = "serenity-interop")]
+impl TryFrom<serenity::builder::CreateEmbed> for Embed {
+    type Error = serde_json::Error;
+
+    /// Example
+    /// ```no_run
+    /// # use serenity::builder::CreateEmbed;
+    /// # use std::convert::TryFrom;
+    /// # use webhook::models::{Embed, Message};
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let embed = CreateEmbed::new().title("Deployed").colour(0x57F287);
+    /// let mut message = Message::new();
+    /// message.embeds.push(Embed::try_from(embed)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn try_from(embed: serenity::builder::CreateEmbed) -> Result<Self, Self::Error> {
+        embed_from(&embed)
+    }
+}
+
+#[cfg(feature = "twilight-interop")]
+impl TryFrom<twilight_model::channel::message::Embed> for Embed {
+    type Error = serde_json::Error;
+
+    /// Accepts the embeds built by `twilight_util::builder::embed::EmbedBuilder` and the
+    /// embeds of received messages.
+    fn try_from(embed: twilight_model::channel::message::Embed) -> Result<Self, Self::Error> {
+        embed_from(&embed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    #[cfg(feature = "serenity-interop")]
+    #[test]
+    fn serenity_embeds_are_converted() {
+        use serenity::builder::{CreateEmbed, CreateEmbedFooter};
+
+        let embed = crate::models::Embed::try_from(
+            CreateEmbed::new()
+                .title("Deployed")
+                .colour(0x57F287)
+                .field("Service", "billing", true)
+                .footer(CreateEmbedFooter::new("ci")),
+        )
+        .unwrap();
+        assert_eq!(embed.title.as_deref(), Some("Deployed"));
+        assert_eq!(embed.color, Some(crate::models::Color::GREEN));
+        assert_eq!(embed.fields[0].value, "billing");
+        assert_eq!(embed.footer.unwrap().text, "ci");
+    }
+
+    #[cfg(feature = "twilight-interop")]
+    #[test]
+    fn twilight_embeds_are_converted() {
+        use std::str::FromStr;
+        use twilight_model::channel::message::embed::{Embed, EmbedField};
+        use twilight_model::util::Timestamp;
+
+        let embed = crate::models::Embed::try_from(Embed {
+            author: None,
+            color: Some(0xED4245),
+            description: Some("failed".to_owned()),
+            fields: vec![EmbedField {
+                inline: false,
+                name: "Step".to_owned(),
+                value: "tests".to_owned(),
+            }],
+            footer: None,
+            image: None,
+            kind: "rich".to_owned(),
+            provider: None,
+            thumbnail: None,
+            timestamp: Some(Timestamp::from_str("2021-08-02T16:56:43.772000+00:00").unwrap()),
+            title: None,
+            url: None,
+            video: None,
+        })
+        .unwrap();
+        assert_eq!(embed.description.as_deref(), Some("failed"));
+        assert_eq!(embed.color, Some(crate::models::Color::RED));
+        assert_eq!(embed.fields[0].name, "Step");
+        assert!(embed.timestamp.is_some());
+    }
+
+    #[cfg(feature = "twilight-interop")]
+    #[test]
+    fn incomplete_twilight_providers_and_videos_are_left_out() {
+        use twilight_model::channel::message::embed::{Embed, EmbedProvider, EmbedVideo};
+
+        let embed = crate::models::Embed::try_from(Embed {
+            author: None,
+            color: None,
+            description: Some("release notes".to_owned()),
+            fields: vec![],
+            footer: None,
+            image: None,
+            kind: "video".to_owned(),
+            provider: Some(EmbedProvider {
+                name: Some("YouTube".to_owned()),
+                url: None,
+            }),
+            thumbnail: None,
+            timestamp: None,
+            title: None,
+            url: None,
+            video: Some(EmbedVideo {
+                height: Some(720),
+                proxy_url: None,
+                url: None,
+                width: Some(1280),
+            }),
+        })
+        .unwrap();
+        assert_eq!(embed.description.as_deref(), Some("release notes"));
+        assert!(embed.provider.is_none());
+        assert!(embed.video.is_none());
+    }
+}
@@ -0,0 +1,150 @@
+//! An append-only file recording the queued messages that have not been delivered yet.
+//!
+//! Every line is a JSON record: `{"op":"add","id":..,"payload":..}` when a message is queued and
+//! `{"op":"done","id":..}` once it was delivered. Opening the journal replays the file and
+//! rewrites it with the pending messages only. A line cut short by a crash is ignored.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Record {
+    Add { id: u64, payload: Value },
+    Done { id: u64 },
+}
+
+pub(crate) struct Journal {
+    state: Mutex<State>,
+}
+
+struct State {
+    file: File,
+    next_id: u64,
+    pending: usize,
+}
+
+impl Journal {
+    /// Opens or creates the journal at `path` and returns the messages that are still
+    /// pending, in the order they were queued.
+    pub(crate) fn open(path: &Path) -> io::Result<(Self, Vec<(u64, Value)>)> {
+        let mut pending = BTreeMap::new();
+        if path.exists() {
+            for line in BufReader::new(File::open(path)?).lines() {
+                match serde_json::from_str(&line?) {
+                    Ok(Record::Add { id, payload }) => {
+                        pending.insert(id, payload);
+                    }
+                    Ok(Record::Done { id }) => {
+                        pending.remove(&id);
+                    }
+                    Err(_) => continue,
+                }
+            }
+        }
+
+        // compact: keep only the pending messages
+        let mut compacted = path.as_os_str().to_owned();
+        compacted.push(".tmp");
+        let compacted = PathBuf::from(compacted);
+        {
+            let mut file = File::create(&compacted)?;
+            for (id, payload) in pending.iter() {
+                write_record(
+                    &mut file,
+                    &Record::Add {
+                        id: *id,
+                        payload: payload.clone(),
+                    },
+                )?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&compacted, path)?;
+
+        let journal = Journal {
+            state: Mutex::new(State {
+                file: OpenOptions::new().append(true).open(path)?,
+                next_id: pending.keys().next_back().map_or(0, |id| id + 1),
+                pending: pending.len(),
+            }),
+        };
+        Ok((journal, pending.into_iter().collect()))
+    }
+
+    /// Durably records a queued message and returns its id.
+    pub(crate) fn add(&self, payload: Value) -> io::Result<u64> {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        write_record(&mut state.file, &Record::Add { id, payload })?;
+        state.file.sync_data()?;
+        state.next_id += 1;
+        state.pending += 1;
+        Ok(id)
+    }
+
+    /// Records that a message no longer needs to be sent. Empties the file once nothing
+    /// is pending.
+    pub(crate) fn done(&self, id: u64) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.pending = state.pending.saturating_sub(1);
+        if state.pending == 0 {
+            state.file.set_len(0)
+        } else {
+            write_record(&mut state.file, &Record::Done { id })
+        }
+    }
+}
+
+fn write_record(file: &mut File, record: &Record) -> io::Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Journal;
+    use serde_json::json;
+    use std::io::Write;
+
+    #[test]
+    fn pending_messages_survive_a_reopen() {
+        let path = std::env::temp_dir().join(format!("webhook-journal-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let (journal, pending) = Journal::open(&path).unwrap();
+        assert!(pending.is_empty());
+        let first = journal.add(json!({"content": "first"})).unwrap();
+        let second = journal.add(json!({"content": "second"})).unwrap();
+        journal.add(json!({"content": "third"})).unwrap();
+        journal.done(second).unwrap();
+        drop(journal);
+        // a record cut short by a crash
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(br#"{"op":"done","#).unwrap();
+        drop(file);
+
+        let (journal, pending) = Journal::open(&path).unwrap();
+        let contents: Vec<_> = pending
+            .iter()
+            .map(|(_, payload)| payload["content"].as_str().unwrap())
+            .collect();
+        assert_eq!(contents, vec!["first", "third"]);
+        assert_eq!(pending[0].0, first);
+
+        for (id, _) in pending {
+            journal.done(id).unwrap();
+        }
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
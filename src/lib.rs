@@ -1,4 +1,79 @@
+pub mod avatar;
+pub mod builder;
+pub mod cdn;
+pub mod fmt;
+#[cfg(feature = "image")]
+pub mod images;
+#[cfg(any(feature = "serenity-interop", feature = "twilight-interop"))]
+mod interop;
+pub mod limits;
 pub mod models;
+pub mod pagination;
+pub mod prelude;
+pub mod slack;
+pub mod template;
+pub mod url;
 
+// lets the derive macros refer to `::webhook` inside this crate as well
+extern crate self as webhook;
+
+#[cfg(feature = "derive")]
+pub use webhook_derive::ToEmbed;
+
+/// The errors of this crate. Failures with a meaning of their own, such as a
+/// `ValidationReport` or a `RetryError`, can be recovered with `downcast_ref`.
+pub type WebhookError = dyn std::error::Error + Send + Sync;
+
+pub type WebhookResult<Type> = std::result::Result<Type, Box<WebhookError>>;
+
+#[cfg(feature = "request")]
+pub mod request;
+
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod broadcast;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod client;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod manager;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod middleware;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod router;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod testing;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod transport;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub mod verify;
+
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub mod breaker;
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub mod heartbeat;
+#[cfg(feature = "journal")]
+mod journal;
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub mod live;
+#[cfg(feature = "log")]
+pub mod logger;
 #[cfg(feature = "client")]
-pub mod client;
\ No newline at end of file
+pub mod queue;
+#[cfg(feature = "client")]
+pub mod schedule;
+#[cfg(feature = "client")]
+pub mod upload;
+#[cfg(feature = "client")]
+pub mod writer;
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub mod ratelimit;
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub mod retry;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(feature = "redaction")]
+pub mod redact;
+
+#[cfg(feature = "metrics")]
+pub mod telemetry;
@@ -0,0 +1,114 @@
+//! Discord's limits on webhook messages, in one place, so data can be checked or trimmed
+//! before it is put into a message.
+//!
+//! `Message::validate` enforces these limits. Lengths are counted in characters, sizes in
+//! bytes. Each limit is also an associated constant of the type it applies to, such as
+//! `Message::CONTENT_LEN_INTERVAL`.
+//!
+//! ```no_run
+//! # use webhook::{fmt, limits};
+//! # let log = std::fs::read_to_string("build.log").unwrap();
+//! let summary = fmt::truncate(&log, limits::EMBED_DESCRIPTION_LEN.max_allowed);
+//! ```
+
+use crate::avatar::Avatar;
+use crate::models::{
+    ActionRow, AllowedMentions, Attachment, Embed, EmbedAuthor, EmbedField, EmbedFooter, Interval,
+    MediaGallery, Message, Section, SelectMenu, SelectMenuCommonBase, SelectMenuOption,
+    WebhookEdit,
+};
+
+/// The length of `Message::content`.
+pub const CONTENT_LEN: Interval<usize> = Message::CONTENT_LEN_INTERVAL;
+/// The number of embeds of a message.
+pub const EMBED_COUNT: Interval<usize> = Message::EMBED_COUNT_INTERVAL;
+/// The combined length of the titles, descriptions, field names and values, footer texts and
+/// author names of all embeds of a message.
+pub const EMBED_TOTAL_TEXT_LEN: Interval<usize> = Message::EMBED_TOTAL_TEXT_LEN_INTERVAL;
+/// The number of action rows of a message.
+pub const ACTION_ROW_COUNT: Interval<usize> = Message::ACTION_ROW_COUNT_INTERVAL;
+/// The number of components of a message laid out with `Message::IS_COMPONENTS_V2`,
+/// nested ones included.
+pub const COMPONENTS_V2_COUNT: Interval<usize> = Message::COMPONENTS_V2_COUNT_INTERVAL;
+/// The combined length of all text displays of a message.
+pub const TEXT_DISPLAY_TOTAL_LEN: Interval<usize> = Message::TEXT_DISPLAY_TOTAL_LEN_INTERVAL;
+/// The number of attachments of a message.
+pub const ATTACHMENT_COUNT: Interval<usize> = Message::ATTACHMENT_COUNT_INTERVAL;
+/// The combined size of the attachments of a message sent to a server without boosts.
+pub const DEFAULT_UPLOAD_SIZE: u64 = Message::DEFAULT_UPLOAD_LIMIT;
+
+/// The length of `Embed::title`.
+pub const EMBED_TITLE_LEN: Interval<usize> = Embed::TITLE_LEN_INTERVAL;
+/// The length of `Embed::description`.
+pub const EMBED_DESCRIPTION_LEN: Interval<usize> = Embed::DESCRIPTION_LEN_INTERVAL;
+/// The number of fields of an embed.
+pub const EMBED_FIELD_COUNT: Interval<usize> = Embed::FIELDS_LEN_INTERVAL;
+/// The value of `Embed::color`.
+pub const EMBED_COLOR: Interval<u32> = Embed::COLOR_INTERVAL;
+/// The length of `EmbedField::name`.
+pub const EMBED_FIELD_NAME_LEN: Interval<usize> = EmbedField::NAME_LEN_INTERVAL;
+/// The length of `EmbedField::value`.
+pub const EMBED_FIELD_VALUE_LEN: Interval<usize> = EmbedField::VALUE_LEN_INTERVAL;
+/// The length of `EmbedFooter::text`.
+pub const EMBED_FOOTER_TEXT_LEN: Interval<usize> = EmbedFooter::TEXT_LEN_INTERVAL;
+/// The length of `EmbedAuthor::name`.
+pub const EMBED_AUTHOR_NAME_LEN: Interval<usize> = EmbedAuthor::NAME_LEN_INTERVAL;
+
+/// The number of buttons of an action row.
+pub const ACTION_ROW_BUTTON_COUNT: Interval<usize> = ActionRow::BUTTON_COUNT_INTERVAL;
+/// The number of select menus of an action row.
+pub const ACTION_ROW_SELECT_MENU_COUNT: Interval<usize> = ActionRow::SELECT_MENU_COUNT_INTERVAL;
+/// The length of a button label.
+pub const BUTTON_LABEL_LEN: Interval<usize> = Message::LABEL_LEN_INTERVAL;
+/// The length of the custom id of a button or select menu.
+pub const CUSTOM_ID_LEN: Interval<usize> = Message::CUSTOM_ID_LEN_INTERVAL;
+/// The length of the placeholder of a select menu.
+pub const SELECT_MENU_PLACEHOLDER_LEN: Interval<usize> =
+    SelectMenuCommonBase::PLACEHOLDER_LEN_INTERVAL;
+/// The minimum number of values chosen in a select menu.
+pub const SELECT_MENU_MIN_VALUES: Interval<u8> = SelectMenuCommonBase::MIN_VALUES_INTERVAL;
+/// The maximum number of values chosen in a select menu.
+pub const SELECT_MENU_MAX_VALUES: Interval<u8> = SelectMenuCommonBase::MAX_VALUES_INTERVAL;
+/// The number of options of a string select menu.
+pub const SELECT_MENU_OPTION_COUNT: Interval<usize> = SelectMenu::OPTION_COUNT_INTERVAL;
+/// The length of the label of a select menu option.
+pub const SELECT_OPTION_LABEL_LEN: Interval<usize> = SelectMenuOption::LABEL_LEN_INTERVAL;
+/// The length of the value of a select menu option.
+pub const SELECT_OPTION_VALUE_LEN: Interval<usize> = SelectMenuOption::VALUE_LEN_INTERVAL;
+/// The length of the description of a select menu option.
+pub const SELECT_OPTION_DESCRIPTION_LEN: Interval<usize> =
+    SelectMenuOption::DESCRIPTION_LEN_INTERVAL;
+
+/// The number of text displays of a section.
+pub const SECTION_TEXT_DISPLAY_COUNT: Interval<usize> = Section::TEXT_DISPLAY_COUNT_INTERVAL;
+/// The number of items of a media gallery.
+pub const MEDIA_GALLERY_ITEM_COUNT: Interval<usize> = MediaGallery::ITEM_COUNT_INTERVAL;
+/// The length of the description of a media gallery item or thumbnail.
+pub const MEDIA_DESCRIPTION_LEN: Interval<usize> = Message::MEDIA_DESCRIPTION_LEN_INTERVAL;
+/// The length of the description of an attachment.
+pub const ATTACHMENT_DESCRIPTION_LEN: Interval<usize> = Attachment::DESCRIPTION_LEN_INTERVAL;
+
+/// The number of user or role ids allowed to be mentioned by `AllowedMentions`.
+pub const ALLOWED_MENTION_ID_COUNT: Interval<usize> = AllowedMentions::ID_COUNT_INTERVAL;
+/// The length of a webhook name or message username.
+pub const WEBHOOK_NAME_LEN: Interval<usize> = WebhookEdit::NAME_LEN_INTERVAL;
+/// The length of the audit log reason of a webhook change.
+pub const AUDIT_LOG_REASON_LEN: Interval<usize> = WebhookEdit::AUDIT_LOG_REASON_LEN_INTERVAL;
+/// The size of an avatar image.
+pub const AVATAR_SIZE: usize = Avatar::MAX_LEN;
+
+#[cfg(test)]
+mod tests {
+    use crate::models::Message;
+
+    #[test]
+    fn limits_match_validation() {
+        let mut message = Message::new();
+        message.content("a".repeat(super::CONTENT_LEN.max_allowed));
+        message.embed(|embed| embed.title("t".repeat(super::EMBED_TITLE_LEN.max_allowed)));
+        assert!(message.validate().is_ok());
+
+        message.content("a".repeat(super::CONTENT_LEN.max_allowed + 1));
+        assert!(message.validate().is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use std::time::{Duration, Instant};
+
+use crate::client::{WebhookClient, WebhookResult};
+use crate::models::{Message, Snowflake};
+use crate::request::validate;
+use crate::transport::sleep;
+
+/// A sent message that is updated in place, e.g. to report progress.
+///
+/// Updates change the message the handle keeps and are edited into the sent message at most
+/// once per `min_interval`; updates in between are only sent with the next edit. Call
+/// `finish` (or `flush`) after the last update so it is not left unsent.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # async fn build_step() {}
+/// # async fn run(client: WebhookClient) -> WebhookResult<()> {
+/// let mut live = client.send_live(|message| message.content("Building... 0%")).await?;
+/// for percent in 1..=100 {
+///     build_step().await;
+///     live.update(|message| message.content(&format!("Building... {}%", percent))).await?;
+/// }
+/// live.finish(|message| message.content("Build finished")).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct LiveMessage<'a> {
+    client: &'a WebhookClient,
+    id: Snowflake,
+    message: Message,
+    min_interval: Duration,
+    edited_at: Instant,
+    pending: bool,
+}
+
+impl<'a> LiveMessage<'a> {
+    pub(crate) fn new(client: &'a WebhookClient, id: Snowflake, message: Message) -> Self {
+        Self {
+            client,
+            id,
+            message,
+            min_interval: Duration::from_secs(1),
+            edited_at: Instant::now(),
+            pending: false,
+        }
+    }
+
+    /// The id of the sent message.
+    pub fn id(&self) -> Snowflake {
+        self.id
+    }
+
+    /// The message as of the last update, sent or not.
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    /// Minimum time between two edits. One second by default.
+    pub fn min_interval(&mut self, min_interval: Duration) -> &mut Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Changes the message and edits it if the last edit is at least `min_interval` ago.
+    ///
+    /// Returns whether the edit was sent. An update that would make the message invalid is
+    /// rejected and leaves the message unchanged.
+    pub async fn update<Func>(&mut self, function: Func) -> WebhookResult<bool>
+    where
+        Func: FnOnce(&mut Message) -> &mut Message,
+    {
+        let mut message = self.message.clone();
+        function(&mut message);
+        validate(&message)?;
+        self.message = message;
+        self.pending = true;
+
+        if self.edited_at.elapsed() < self.min_interval {
+            return Ok(false);
+        }
+        self.edit().await?;
+        Ok(true)
+    }
+
+    /// Sends the pending update, waiting for the rest of `min_interval` first.
+    pub async fn flush(&mut self) -> WebhookResult<()> {
+        if !self.pending {
+            return Ok(());
+        }
+        if let Some(wait) = self.min_interval.checked_sub(self.edited_at.elapsed()) {
+            sleep(wait).await;
+        }
+        self.edit().await
+    }
+
+    /// Applies a last update and sends it.
+    pub async fn finish<Func>(mut self, function: Func) -> WebhookResult<()>
+    where
+        Func: FnOnce(&mut Message) -> &mut Message,
+    {
+        self.update(function).await?;
+        self.flush().await
+    }
+
+    async fn edit(&mut self) -> WebhookResult<()> {
+        self.client.edit_message(self.id, &self.message).await?;
+        self.edited_at = Instant::now();
+        self.pending = false;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use crate::client::WebhookClient;
+    use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+    use crate::WebhookResult;
+    use futures_util::future::BoxFuture;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Records method and content of every request and answers like Discord.
+    struct FakeDiscord(Arc<Mutex<Vec<(String, String)>>>);
+
+    impl WebhookTransport for FakeDiscord {
+        fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+            let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+            self.0.lock().unwrap().push((
+                request.method().to_string(),
+                body["content"].as_str().unwrap().to_owned(),
+            ));
+            Box::pin(async {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .body(br#"{"id": "42"}"#.to_vec())?)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn updates_are_debounced() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(FakeDiscord(requests.clone()))
+            .build()
+            .unwrap();
+
+        let mut live = client
+            .send_live(|message| message.content("0%"))
+            .await
+            .unwrap();
+        live.min_interval(Duration::from_millis(50));
+        assert_eq!(live.id().get(), 42);
+
+        assert!(!live.update(|message| message.content("10%")).await.unwrap());
+        assert!(live
+            .update(|message| message.content("a".repeat(2001)))
+            .await
+            .is_err());
+        assert_eq!(live.message().content.as_deref(), Some("10%"));
+        live.flush().await.unwrap();
+        assert!(!live.update(|message| message.content("20%")).await.unwrap());
+        live.finish(|message| message.content("done"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                ("POST".to_owned(), "0%".to_owned()),
+                ("PATCH".to_owned(), "10%".to_owned()),
+                ("PATCH".to_owned(), "done".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,475 @@
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::fmt::{role_mention, truncate};
+use crate::models::{Color, Embed, EmbedField, Message, Snowflake};
+use crate::queue::WebhookQueue;
+
+/// A `log` backend that posts every record as an embed through a `WebhookQueue`.
+///
+/// The embed is titled with the level, colored by it and lists the target, module path and
+/// source location; `level_style` changes the color, adds an emoji or mentions a role per
+/// level. Records are queued without waiting: when the queue is full they are dropped.
+/// Records of the HTTP stack (and of this crate) are ignored by default, as sending a message
+/// would otherwise log the next one.
+///
+/// With `batch`, less severe records are collected and sent up to ten embeds at a time,
+/// while more severe ones are sent right away.
+///
+/// `Log::flush` queues the batched records but cannot wait for the background task; call
+/// `WebhookQueue::flush` or `WebhookQueue::shutdown` before the program exits instead.
+///
+/// Example
+/// ```no_run
+/// # use log::{Level, LevelFilter};
+/// # use std::time::Duration;
+/// # use webhook::logger::WebhookLogger;
+/// # use webhook::prelude::*;
+/// # use webhook::queue::WebhookQueue;
+/// # const URL: &str = "https://discord.com/api/webhooks/0/token";
+/// # const ON_CALL_ROLE: u64 = 42;
+/// # async fn run() -> WebhookResult<()> {
+/// let queue = WebhookQueue::spawn(WebhookClient::new(URL));
+/// let mut logger = WebhookLogger::new(queue.clone());
+/// logger
+///     .level(LevelFilter::Info)
+///     .username("my-service")
+///     .level_style(Level::Error, |style| style.emoji("🔥").mention_role(ON_CALL_ROLE))
+///     .batch(Level::Info, Duration::from_secs(30));
+/// logger.init()?;
+///
+/// log::error!("payment provider unreachable");
+/// queue.shutdown(Duration::from_secs(5)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebhookLogger {
+    queue: WebhookQueue,
+    level: LevelFilter,
+    username: Option<String>,
+    ignored_targets: Vec<String>,
+    styles: HashMap<Level, LevelStyle>,
+    /// The most severe level that is batched, and how long records wait for others.
+    batching: Option<(Level, Duration)>,
+    batch: Arc<Mutex<Batch>>,
+}
+
+/// How the records of a level are presented, see `WebhookLogger::level_style`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelStyle {
+    color: Color,
+    emoji: Option<String>,
+    mention_role: Option<Snowflake>,
+}
+
+impl LevelStyle {
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            emoji: None,
+            mention_role: None,
+        }
+    }
+
+    /// The default style of `level`: red errors, yellow warnings, blurple infos, green debug
+    /// and white trace records.
+    pub fn of(level: Level) -> Self {
+        Self::new(match level {
+            Level::Error => Color::RED,
+            Level::Warn => Color::YELLOW,
+            Level::Info => Color::BLURPLE,
+            Level::Debug => Color::GREEN,
+            Level::Trace => Color::WHITE,
+        })
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    /// An emoji put before the level in the title, e.g. `🔥`.
+    pub fn emoji(&mut self, emoji: &str) -> &mut Self {
+        self.emoji = Some(emoji.to_owned());
+        self
+    }
+
+    /// Mentions the role in the message, pinging its members.
+    pub fn mention_role<Id: Into<Snowflake>>(&mut self, role_id: Id) -> &mut Self {
+        self.mention_role = Some(role_id.into());
+        self
+    }
+}
+
+/// Records waiting to be sent together, see `WebhookLogger::batch`.
+#[derive(Default)]
+struct Batch {
+    embeds: Vec<Embed>,
+    roles: Vec<Snowflake>,
+    /// Counts the sent batches, so the timer of a batch that was sent early, because it was
+    /// full, leaves the next batch alone.
+    generation: u64,
+}
+
+impl Batch {
+    /// Whether `embed` still fits into the message of the batch.
+    fn fits(&self, embed: &Embed) -> bool {
+        self.embeds.len() < Message::EMBED_COUNT_INTERVAL.max_allowed
+            && self.embeds.iter().map(Embed::char_count).sum::<usize>() + embed.char_count()
+                <= Message::EMBED_TOTAL_TEXT_LEN_INTERVAL.max_allowed
+    }
+
+    /// Queues the batched records, if any.
+    fn send(&mut self, queue: &WebhookQueue, username: Option<&str>) {
+        if self.embeds.is_empty() {
+            return;
+        }
+        let message = records_message(
+            username,
+            std::mem::take(&mut self.embeds),
+            std::mem::take(&mut self.roles),
+        );
+        self.generation += 1;
+        let _ = queue.try_enqueue(message);
+    }
+}
+
+impl WebhookLogger {
+    /// Creates a logger for records of level `Info` and above.
+    pub fn new(queue: WebhookQueue) -> Self {
+        Self {
+            queue,
+            level: LevelFilter::Info,
+            username: None,
+            ignored_targets: [
+                "webhook",
+                "hyper",
+                "h2",
+                "tokio",
+                "mio",
+                "want",
+                "native_tls",
+            ]
+            .iter()
+            .map(|target| (*target).to_owned())
+            .collect(),
+            styles: HashMap::new(),
+            batching: None,
+            batch: Arc::new(Mutex::new(Batch::default())),
+        }
+    }
+
+    /// The most verbose level that is sent.
+    pub fn level(&mut self, level: LevelFilter) -> &mut Self {
+        self.level = level;
+        self
+    }
+
+    /// Username the messages are posted with, instead of the webhook's name.
+    pub fn username(&mut self, username: &str) -> &mut Self {
+        self.username = Some(username.to_owned());
+        self
+    }
+
+    /// Ignores records whose target is `target` or one of its submodules.
+    pub fn ignore_target(&mut self, target: &str) -> &mut Self {
+        self.ignored_targets.push(target.to_owned());
+        self
+    }
+
+    /// Changes how records of `level` are presented, starting from its current style.
+    pub fn level_style<Func>(&mut self, level: Level, function: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut LevelStyle) -> &mut LevelStyle,
+    {
+        let style = self
+            .styles
+            .entry(level)
+            .or_insert_with(|| LevelStyle::of(level));
+        function(style);
+        self
+    }
+
+    /// Collects records of `level` and less severe levels for up to `window` and sends them
+    /// together, up to ten per message. More severe records are sent right away.
+    ///
+    /// The window is timed by a tokio task; records logged outside of a tokio runtime wait
+    /// for the next batch or `Log::flush`.
+    pub fn batch(&mut self, level: Level, window: Duration) -> &mut Self {
+        self.batching = Some((level, window));
+        self
+    }
+
+    /// Installs the logger as the global `log` backend.
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+
+    fn style(&self, level: Level) -> LevelStyle {
+        self.styles
+            .get(&level)
+            .cloned()
+            .unwrap_or_else(|| LevelStyle::of(level))
+    }
+
+    fn embed(&self, record: &Record, style: &LevelStyle) -> Embed {
+        let title = match style.emoji.as_ref() {
+            Some(emoji) => format!("{} {}", emoji, record.level()),
+            None => record.level().to_string(),
+        };
+        let mut embed = Embed::new();
+        embed
+            .title(&title)
+            .description(truncate(
+                &record.args().to_string(),
+                Embed::DESCRIPTION_LEN_INTERVAL.max_allowed,
+            ))
+            .color(style.color)
+            .timestamp_now();
+        embed.fields.push(field("Target", record.target()));
+        if let Some(module_path) = record.module_path() {
+            embed.fields.push(field("Module", module_path));
+        }
+        if let (Some(file), Some(line)) = (record.file(), record.line()) {
+            embed
+                .fields
+                .push(field("Location", &format!("{}:{}", file, line)));
+        }
+        embed
+    }
+
+    /// Adds the record to the batch, sending the batch first if it is full.
+    fn batch_record(&self, embed: Embed, role: Option<Snowflake>, window: Duration) {
+        let mut batch = self.batch.lock().unwrap();
+        if !batch.fits(&embed) {
+            batch.send(&self.queue, self.username.as_deref());
+        }
+        let first = batch.embeds.is_empty();
+        let generation = batch.generation;
+        batch.embeds.push(embed);
+        if let Some(role) = role.filter(|role| !batch.roles.contains(role)) {
+            batch.roles.push(role);
+        }
+        drop(batch);
+
+        if first {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                let batch = self.batch.clone();
+                let queue = self.queue.clone();
+                let username = self.username.clone();
+                runtime.spawn(async move {
+                    tokio::time::sleep(window).await;
+                    let mut batch = batch.lock().unwrap();
+                    if batch.generation == generation {
+                        batch.send(&queue, username.as_deref());
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// A message with the embeds of records, mentioning `roles`.
+fn records_message(username: Option<&str>, embeds: Vec<Embed>, roles: Vec<Snowflake>) -> Message {
+    let mut message = Message::new();
+    if let Some(username) = username {
+        message.username(username);
+    }
+    if !roles.is_empty() {
+        let mentions: Vec<_> = roles.iter().map(|role| role_mention(*role)).collect();
+        message
+            .content(mentions.join(" "))
+            .allowed_mentions(|mentions| mentions.roles(roles));
+    }
+    message.embeds = embeds;
+    message
+}
+
+impl Log for WebhookLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+            && !self.ignored_targets.iter().any(|ignored| {
+                metadata.target() == ignored
+                    || metadata
+                        .target()
+                        .strip_prefix(ignored.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let style = self.style(record.level());
+        let embed = self.embed(record, &style);
+        match self.batching {
+            // less severe levels compare greater
+            Some((level, window)) if record.level() >= level => {
+                self.batch_record(embed, style.mention_role, window)
+            }
+            _ => {
+                let roles = style.mention_role.into_iter().collect();
+                let message = records_message(self.username.as_deref(), vec![embed], roles);
+                let _ = self.queue.try_enqueue(message);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.batch
+            .lock()
+            .unwrap()
+            .send(&self.queue, self.username.as_deref());
+    }
+}
+
+fn field(name: &str, value: &str) -> EmbedField {
+    EmbedField::new(
+        name,
+        truncate(value, EmbedField::VALUE_LEN_INTERVAL.max_allowed),
+        true,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebhookLogger;
+    use crate::models::Color;
+    use crate::queue::WebhookQueue;
+    use crate::testing::{MockTransport, RecordedRequest};
+    use log::{Level, LevelFilter, Log, Record};
+    use std::time::Duration;
+
+    /// The JSON payloads sent through `mock`.
+    fn sent(mock: &MockTransport) -> Vec<serde_json::Value> {
+        mock.requests()
+            .iter()
+            .filter_map(RecordedRequest::json)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn records_are_sent_as_embeds() {
+        let mock = MockTransport::new();
+        let queue = WebhookQueue::spawn(mock.client());
+        let mut logger = WebhookLogger::new(queue.clone());
+        logger.level(LevelFilter::Warn).username("my-service");
+
+        logger.log(
+            &Record::builder()
+                .args(format_args!("disk {} full", "/var"))
+                .level(Level::Error)
+                .target("app::storage")
+                .module_path(Some("app::storage"))
+                .file(Some("src/storage.rs"))
+                .line(Some(42))
+                .build(),
+        );
+        logger.log(
+            &Record::builder()
+                .args(format_args!("too verbose"))
+                .level(Level::Info)
+                .target("app")
+                .build(),
+        );
+        logger.log(
+            &Record::builder()
+                .args(format_args!("connection closed"))
+                .level(Level::Error)
+                .target("hyper::client")
+                .build(),
+        );
+        queue.flush().await.unwrap();
+
+        let sent = sent(&mock);
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0]["username"], "my-service");
+        let embed = &sent[0]["embeds"][0];
+        assert_eq!(embed["title"], "ERROR");
+        assert_eq!(embed["description"], "disk /var full");
+        assert_eq!(embed["color"], 0xED4245);
+        assert_eq!(embed["fields"][0]["value"], "app::storage");
+        assert_eq!(embed["fields"][2]["value"], "src/storage.rs:42");
+    }
+
+    #[tokio::test]
+    async fn levels_are_styled_and_batched() {
+        let mock = MockTransport::new();
+        let queue = WebhookQueue::spawn(mock.client());
+        let mut logger = WebhookLogger::new(queue.clone());
+        logger
+            .level_style(Level::Error, |style| style.emoji("🔥").mention_role(42u64))
+            .level_style(Level::Info, |style| style.color(Color::GREEN))
+            .batch(Level::Info, Duration::from_millis(50));
+
+        let record = |level: Level, text: &'static str| {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("{}", text))
+                    .level(level)
+                    .target("app")
+                    .build(),
+            );
+        };
+        record(Level::Info, "started");
+        record(Level::Error, "crashed");
+        record(Level::Info, "restarted");
+        queue.flush().await.unwrap();
+        assert_eq!(sent(&mock).len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        queue.flush().await.unwrap();
+        let sent = sent(&mock);
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0]["content"], "<@&42>");
+        assert_eq!(sent[0]["allowed_mentions"]["roles"][0], "42");
+        assert_eq!(sent[0]["embeds"][0]["title"], "🔥 ERROR");
+        let batched = sent[1]["embeds"].as_array().unwrap();
+        assert_eq!(batched.len(), 2);
+        assert_eq!(batched[0]["description"], "started");
+        assert_eq!(batched[1]["description"], "restarted");
+        assert_eq!(batched[1]["color"], Color::GREEN.0);
+        assert!(sent[1]["content"].is_null());
+    }
+    #[tokio::test]
+    async fn full_batches_do_not_shorten_the_next_window() {
+        let mock = MockTransport::new();
+        let queue = WebhookQueue::spawn(mock.client());
+        let mut logger = WebhookLogger::new(queue.clone());
+        logger.batch(Level::Info, Duration::from_millis(200));
+
+        let record = |text: &'static str| {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("{}", text))
+                    .level(Level::Info)
+                    .target("app")
+                    .build(),
+            );
+        };
+        record("first");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        for _ in 0..10 {
+            record("more");
+        }
+        queue.flush().await.unwrap();
+        assert_eq!(sent(&mock).len(), 1);
+
+        // the window of the first batch is over, the one of the second is not
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        queue.flush().await.unwrap();
+        assert_eq!(sent(&mock).len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        queue.flush().await.unwrap();
+        let sent = sent(&mock);
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1]["embeds"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,311 @@
+use http::{header, Method, Request, StatusCode};
+use std::sync::Arc;
+
+use crate::client::{api_error, WebhookResult};
+use crate::models::{Snowflake, Webhook, WebhookEdit};
+use crate::request::{audit_log_reason_value, AUDIT_LOG_REASON};
+use crate::transport::{
+    DefaultTransport, HttpRequest, HttpResponse, TransportConfig, WebhookTransport,
+};
+
+const API_BASE: &str = "https://discord.com/api/v10";
+
+/// Creates, lists, modifies and deletes webhooks on behalf of a bot.
+///
+/// The bot needs the `MANAGE_WEBHOOKS` permission in the affected channels. The reason for a
+/// change can be recorded in the audit log of the guild with `WebhookEdit::audit_log_reason`
+/// and `delete_with_reason`.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # async fn run(channel_id: Snowflake) -> WebhookResult<()> {
+/// let manager = WebhookManager::new("BOT TOKEN");
+/// let webhook = manager
+///     .create(channel_id, |webhook| webhook.name("Deployments"))
+///     .await?;
+/// let token = webhook.token.as_deref().ok_or("incoming webhooks have a token")?;
+/// let client = WebhookClient::new(&format!(
+///     "https://discord.com/api/webhooks/{}/{}",
+///     webhook.id, token
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebhookManager {
+    transport: Arc<dyn WebhookTransport>,
+    authorization: String,
+}
+
+impl WebhookManager {
+    pub fn new(bot_token: &str) -> Self {
+        let transport = DefaultTransport::new(&TransportConfig::default())
+            .expect("the default transport configuration is always valid");
+        Self::with_transport(bot_token, transport)
+    }
+
+    /// Creates a manager that executes its requests with a custom HTTP stack.
+    pub fn with_transport<Transport>(bot_token: &str, transport: Transport) -> Self
+    where
+        Transport: WebhookTransport + 'static,
+    {
+        WebhookManager {
+            transport: Arc::new(transport),
+            authorization: format!("Bot {}", bot_token),
+        }
+    }
+
+    /// Creates a webhook in a channel. A name is required.
+    pub async fn create<Func>(
+        &self,
+        channel_id: Snowflake,
+        function: Func,
+    ) -> WebhookResult<Webhook>
+    where
+        Func: FnOnce(&mut WebhookEdit) -> &mut WebhookEdit,
+    {
+        let mut edit = WebhookEdit::new();
+        function(&mut edit);
+        if edit.name.is_none() {
+            return Err("A webhook needs a name to be created".into());
+        }
+        edit.validate()?;
+
+        let path = format!("/channels/{}/webhooks", channel_id);
+        let reason = edit.audit_log_reason.as_deref();
+        self.request(Method::POST, &path, reason, serde_json::to_vec(&edit)?)
+            .await
+    }
+
+    pub async fn channel_webhooks(&self, channel_id: Snowflake) -> WebhookResult<Vec<Webhook>> {
+        let path = format!("/channels/{}/webhooks", channel_id);
+        self.request(Method::GET, &path, None, vec![]).await
+    }
+
+    pub async fn guild_webhooks(&self, guild_id: Snowflake) -> WebhookResult<Vec<Webhook>> {
+        let path = format!("/guilds/{}/webhooks", guild_id);
+        self.request(Method::GET, &path, None, vec![]).await
+    }
+
+    pub async fn get(&self, webhook_id: Snowflake) -> WebhookResult<Webhook> {
+        let path = format!("/webhooks/{}", webhook_id);
+        self.request(Method::GET, &path, None, vec![]).await
+    }
+
+    /// Changes the name, avatar or channel of a webhook.
+    pub async fn modify<Func>(
+        &self,
+        webhook_id: Snowflake,
+        function: Func,
+    ) -> WebhookResult<Webhook>
+    where
+        Func: FnOnce(&mut WebhookEdit) -> &mut WebhookEdit,
+    {
+        let mut edit = WebhookEdit::new();
+        function(&mut edit);
+        edit.validate()?;
+
+        let path = format!("/webhooks/{}", webhook_id);
+        let reason = edit.audit_log_reason.as_deref();
+        self.request(Method::PATCH, &path, reason, serde_json::to_vec(&edit)?)
+            .await
+    }
+
+    pub async fn delete(&self, webhook_id: Snowflake) -> WebhookResult<()> {
+        self.delete_webhook(webhook_id, None).await
+    }
+
+    /// Deletes a webhook, recording `reason` in the audit log of the guild.
+    pub async fn delete_with_reason(
+        &self,
+        webhook_id: Snowflake,
+        reason: &str,
+    ) -> WebhookResult<()> {
+        self.delete_webhook(webhook_id, Some(reason)).await
+    }
+
+    async fn delete_webhook(
+        &self,
+        webhook_id: Snowflake,
+        reason: Option<&str>,
+    ) -> WebhookResult<()> {
+        let path = format!("/webhooks/{}", webhook_id);
+        let response = self.execute(Method::DELETE, &path, reason, vec![]).await?;
+
+        if response.status() == StatusCode::NO_CONTENT {
+            Ok(())
+        } else {
+            Err(api_error(response))
+        }
+    }
+
+    async fn request<T>(
+        &self,
+        method: Method,
+        path: &str,
+        reason: Option<&str>,
+        body: Vec<u8>,
+    ) -> WebhookResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let response = self.execute(method, path, reason, body).await?;
+
+        if response.status().is_success() {
+            Ok(serde_json::from_slice(response.body())?)
+        } else {
+            Err(api_error(response))
+        }
+    }
+
+    async fn execute(
+        &self,
+        method: Method,
+        path: &str,
+        reason: Option<&str>,
+        body: Vec<u8>,
+    ) -> WebhookResult<HttpResponse> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(format!("{}{}", API_BASE, path))
+            .header(header::AUTHORIZATION, &self.authorization)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(reason) = reason {
+            builder = builder.header(AUDIT_LOG_REASON, audit_log_reason_value(reason));
+        }
+        let request: HttpRequest = builder.body(body)?;
+        self.transport.execute(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebhookManager;
+    use crate::models::Snowflake;
+    use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+    use crate::WebhookResult;
+    use futures_util::future::BoxFuture;
+    use http::{header, Method};
+    use std::sync::{Arc, Mutex};
+
+    const WEBHOOK: &str = r#"{"id":"1","type":1,"guild_id":"2","channel_id":"3","name":"hook","avatar":null,"token":"t","application_id":null}"#;
+
+    struct CannedTransport {
+        status: u16,
+        body: &'static str,
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl WebhookTransport for CannedTransport {
+        fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+            self.requests.lock().unwrap().push(request);
+            Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(self.status)
+                    .body(self.body.as_bytes().to_vec())?)
+            })
+        }
+    }
+
+    fn canned_manager(
+        status: u16,
+        body: &'static str,
+    ) -> (WebhookManager, Arc<Mutex<Vec<HttpRequest>>>) {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let transport = CannedTransport {
+            status,
+            body,
+            requests: requests.clone(),
+        };
+        (
+            WebhookManager::with_transport("secret", transport),
+            requests,
+        )
+    }
+
+    #[tokio::test]
+    async fn create_posts_to_the_channel() {
+        let (manager, requests) = canned_manager(200, WEBHOOK);
+        let webhook = manager
+            .create(Snowflake(3), |webhook| webhook.name("hook"))
+            .await
+            .unwrap();
+        assert_eq!(webhook.name.as_deref(), Some("hook"));
+        assert_eq!(webhook.token.as_deref(), Some("t"));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].method(), Method::POST);
+        assert_eq!(
+            requests[0].uri(),
+            "https://discord.com/api/v10/channels/3/webhooks"
+        );
+        assert_eq!(requests[0].headers()[header::AUTHORIZATION], "Bot secret");
+        assert_eq!(requests[0].body(), br#"{"name":"hook"}"#);
+    }
+
+    #[tokio::test]
+    async fn audit_log_reasons_are_sent() {
+        let (manager, requests) = canned_manager(200, WEBHOOK);
+        manager
+            .modify(Snowflake(1), |webhook| {
+                webhook
+                    .name("hook")
+                    .audit_log_reason("Renamed for the déploiement")
+            })
+            .await
+            .unwrap();
+        let (deleting, deletes) = canned_manager(204, "");
+        deleting
+            .delete_with_reason(Snowflake(1), "No longer used")
+            .await
+            .unwrap();
+        let too_long = manager
+            .modify(Snowflake(1), |webhook| {
+                webhook.audit_log_reason("a".repeat(513))
+            })
+            .await;
+        assert!(too_long.is_err());
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            requests[0].headers()["x-audit-log-reason"],
+            "Renamed%20for%20the%20d%C3%A9ploiement"
+        );
+        assert_eq!(requests[0].body(), br#"{"name":"hook"}"#);
+        assert_eq!(
+            deletes.lock().unwrap()[0].headers()["x-audit-log-reason"],
+            "No%20longer%20used"
+        );
+    }
+
+    #[tokio::test]
+    async fn create_requires_a_name() {
+        let (manager, requests) = canned_manager(200, WEBHOOK);
+        assert!(manager
+            .create(Snowflake(3), |webhook| webhook)
+            .await
+            .is_err());
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn list_and_delete() {
+        let (manager, _) = canned_manager(200, "[]");
+        assert!(manager
+            .guild_webhooks(Snowflake(2))
+            .await
+            .unwrap()
+            .is_empty());
+
+        let (manager, requests) = canned_manager(204, "");
+        manager.delete(Snowflake(1)).await.unwrap();
+        assert_eq!(requests.lock().unwrap()[0].method(), Method::DELETE);
+        assert!(!requests.lock().unwrap()[0]
+            .headers()
+            .contains_key("x-audit-log-reason"));
+
+        let (manager, _) = canned_manager(403, r#"{"message":"Missing Permissions"}"#);
+        let error = manager.delete(Snowflake(1)).await.unwrap_err();
+        assert!(error.to_string().contains("Missing Permissions"));
+    }
+}
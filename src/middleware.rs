@@ -0,0 +1,220 @@
+//! Middleware that sees every request of a client before it reaches the transport.
+//!
+//! A middleware can change the request, inspect or replace the response, or call the rest of
+//! the chain several times, e.g. to retry. Middlewares registered with
+//! `WebhookClientBuilder::middleware` run in the order they were added, after the rate
+//! limiter and circuit breaker of the client and inside its retry policy.
+//!
+//! Example
+//! ```no_run
+//! # use futures_util::future::BoxFuture;
+//! # use http::{HeaderValue, StatusCode};
+//! # use webhook::middleware::{Middleware, Next};
+//! # use webhook::prelude::*;
+//! # use webhook::transport::{HttpRequest, HttpResponse};
+//! # fn audit_log(status: StatusCode) {}
+//! # fn run(url: &str) -> WebhookResult<()> {
+//! struct Audit;
+//!
+//! impl Middleware for Audit {
+//!     fn handle<'a>(
+//!         &'a self,
+//!         mut request: HttpRequest,
+//!         next: Next<'a>,
+//!     ) -> BoxFuture<'a, WebhookResult<HttpResponse>> {
+//!         request.headers_mut().insert("x-audit-id", HeaderValue::from_static("deploys"));
+//!         Box::pin(async move {
+//!             let response = next.run(request).await?;
+//!             audit_log(response.status());
+//!             Ok(response)
+//!         })
+//!     }
+//! }
+//!
+//! let client = WebhookClient::builder(url).middleware(Audit).build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+
+use crate::transport::{HttpBodyStream, HttpRequest, HttpResponse, WebhookTransport};
+use crate::WebhookResult;
+
+/// A step in the request chain of a client, see the module documentation.
+pub trait Middleware: Send + Sync {
+    /// Handles `request`, usually by passing it on with `next.run`.
+    fn handle<'a>(
+        &'a self,
+        request: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, WebhookResult<HttpResponse>>;
+}
+
+/// The rest of the chain after a middleware: the following middlewares and the transport.
+///
+/// `Next` is `Copy`, so a middleware can run the rest of the chain more than once.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    middlewares: &'a [Arc<dyn Middleware>],
+    transport: &'a dyn WebhookTransport,
+}
+
+impl<'a> Next<'a> {
+    pub fn run(self, request: HttpRequest) -> BoxFuture<'a, WebhookResult<HttpResponse>> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => middleware.handle(
+                request,
+                Next {
+                    middlewares,
+                    transport: self.transport,
+                },
+            ),
+            None => self.transport.execute(request),
+        }
+    }
+}
+
+/// A copy of `request` without its extensions, for middlewares that send a request more than
+/// once; `http::Request` is not `Clone`.
+pub fn clone_request(request: &HttpRequest) -> HttpRequest {
+    let mut copy = http::Request::new(request.body().clone());
+    *copy.method_mut() = request.method().clone();
+    *copy.uri_mut() = request.uri().clone();
+    *copy.version_mut() = request.version();
+    *copy.headers_mut() = request.headers().clone();
+    copy
+}
+
+/// A transport running requests through middlewares before the wrapped transport.
+///
+/// Streamed requests are passed to the wrapped transport directly, since middlewares take
+/// requests with their whole body, and so are the size limited downloads of files attached by
+/// URL, which are not requests to Discord.
+pub(crate) struct MiddlewareTransport {
+    pub(crate) middlewares: Vec<Arc<dyn Middleware>>,
+    pub(crate) transport: Arc<dyn WebhookTransport>,
+}
+
+impl WebhookTransport for MiddlewareTransport {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        Next {
+            middlewares: &self.middlewares,
+            transport: &*self.transport,
+        }
+        .run(request)
+    }
+
+    fn execute_stream(
+        &self,
+        request: http::Request<HttpBodyStream>,
+    ) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        self.transport.execute_stream(request)
+    }
+
+    fn execute_limited(
+        &self,
+        request: HttpRequest,
+        max_body_size: usize,
+    ) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        self.transport.execute_limited(request, max_body_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clone_request, Middleware, MiddlewareTransport, Next};
+    use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+    use crate::WebhookResult;
+    use futures_util::future::BoxFuture;
+    use http::{HeaderValue, StatusCode};
+    use std::sync::{Arc, Mutex};
+
+    /// Answers with the value of the `x-order` header as the body.
+    struct Echo;
+
+    impl WebhookTransport for Echo {
+        fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+            let order = request.headers()["x-order"].as_bytes().to_vec();
+            Box::pin(async move { Ok(http::Response::new(order)) })
+        }
+    }
+
+    struct Append(&'static str);
+
+    impl Middleware for Append {
+        fn handle<'a>(
+            &'a self,
+            mut request: HttpRequest,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, WebhookResult<HttpResponse>> {
+            let order = match request.headers().get("x-order") {
+                Some(order) => format!("{}{}", order.to_str().unwrap(), self.0),
+                None => self.0.to_owned(),
+            };
+            request
+                .headers_mut()
+                .insert("x-order", HeaderValue::from_str(&order).unwrap());
+            next.run(request)
+        }
+    }
+
+    /// Runs the rest of the chain a second time if it answered with a server error.
+    struct RetryOnce(Arc<Mutex<u32>>);
+
+    impl Middleware for RetryOnce {
+        fn handle<'a>(
+            &'a self,
+            request: HttpRequest,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, WebhookResult<HttpResponse>> {
+            Box::pin(async move {
+                let response = next.run(clone_request(&request)).await?;
+                if !response.status().is_server_error() {
+                    return Ok(response);
+                }
+                *self.0.lock().unwrap() += 1;
+                next.run(request).await
+            })
+        }
+    }
+
+    struct FailFirst(Mutex<bool>);
+
+    impl Middleware for FailFirst {
+        fn handle<'a>(
+            &'a self,
+            request: HttpRequest,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, WebhookResult<HttpResponse>> {
+            let failed = std::mem::replace(&mut *self.0.lock().unwrap(), true);
+            if failed {
+                return next.run(request);
+            }
+            Box::pin(async move {
+                let mut response = http::Response::new(vec![]);
+                *response.status_mut() = StatusCode::BAD_GATEWAY;
+                Ok(response)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn middlewares_run_in_order() {
+        let retries = Arc::new(Mutex::new(0));
+        let transport = MiddlewareTransport {
+            middlewares: vec![
+                Arc::new(Append("a")),
+                Arc::new(RetryOnce(retries.clone())),
+                Arc::new(Append("b")),
+                Arc::new(FailFirst(Mutex::new(false))),
+            ],
+            transport: Arc::new(Echo),
+        };
+
+        let response = transport.execute(http::Request::new(vec![])).await.unwrap();
+        assert_eq!(response.body(), b"ab");
+        assert_eq!(*retries.lock().unwrap(), 1);
+    }
+}
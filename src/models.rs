@@ -1,8 +1,105 @@
-use serde::{Deserialize, Serialize, Serializer};
-use std::collections::HashSet;
-use std::fmt::Display;
-type Snowflake = String;
+use serde::de::{self, Visitor};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display};
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::avatar::Avatar;
+use crate::fmt::{split_text, truncate};
+#[cfg(feature = "image")]
+use crate::images::ImagePolicy;
+use crate::url::redact_token;
+
+/// A Discord id. Serialized as a string, like the Discord API does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Snowflake(pub u64);
+
+impl Snowflake {
+    /// 2015-01-01T00:00:00Z, the first second of the Discord epoch, in Unix milliseconds.
+    const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+
+    pub const fn new(id: u64) -> Self {
+        Snowflake(id)
+    }
+
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// When the id was generated, i.e. when the user, role, emoji, ... was created.
+    pub fn created_at(self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis((self.0 >> 22) + Self::DISCORD_EPOCH)
+    }
+}
+
+impl From<u64> for Snowflake {
+    fn from(id: u64) -> Self {
+        Snowflake(id)
+    }
+}
+
+impl From<Snowflake> for u64 {
+    fn from(id: Snowflake) -> Self {
+        id.0
+    }
+}
+
+impl FromStr for Snowflake {
+    type Err = ParseIntError;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        id.parse().map(Snowflake)
+    }
+}
+
+impl Display for Snowflake {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Serialize for Snowflake {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Snowflake {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SnowflakeVisitor;
+
+        impl<'de> Visitor<'de> for SnowflakeVisitor {
+            type Value = Snowflake;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a snowflake id as a string or an integer")
+            }
 
+            fn visit_u64<E: de::Error>(self, id: u64) -> Result<Snowflake, E> {
+                Ok(Snowflake(id))
+            }
+
+            fn visit_str<E: de::Error>(self, id: &str) -> Result<Snowflake, E> {
+                id.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(SnowflakeVisitor)
+    }
+}
+
+/// An inclusive range of allowed values, the form of Discord's limits. The limits are
+/// associated constants of the types they apply to, and all of them are listed in `limits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Interval<T> {
     pub max_allowed: T,
     pub min_allowed: T,
@@ -23,28 +120,276 @@ impl<T: Ord> Interval<T> {
 
 macro_rules! interval_member {
 ($name:ident, $option_inner_t:ty, $lower_bound:expr, $upper_bound:expr) => {
-        pub(crate) const $name : Interval<$option_inner_t> = Interval::from_min_max($lower_bound, $upper_bound);
+        pub const $name : Interval<$option_inner_t> = Interval::from_min_max($lower_bound, $upper_bound);
+    };
+}
+
+/// a macro which generates the builder functions adding layout components, delegating to a
+/// `push_layout` function of `self`
+macro_rules! layout_builders {
+    () => {
+        /// Adds a block of markdown text.
+        pub fn text_display(&mut self, content: impl Into<String>) -> &mut Self {
+            self.push_layout(LayoutComponent::TextDisplay(TextDisplay::new(content)));
+            self
+        }
+
+        /// Adds up to three blocks of text next to a thumbnail or a button.
+        ///
+        /// Example
+        /// ```no_run
+        /// # let mut message = webhook::models::Message::new();
+        /// message.section(|section| section
+        ///     .text("**Deploy finished**")
+        ///     .text("billing 1.2.3 is live")
+        ///     .thumbnail("https://example.com/logo.png", |thumbnail| thumbnail));
+        /// ```
+        pub fn section<Func>(&mut self, func: Func) -> &mut Self
+        where
+            Func: FnOnce(&mut Section) -> &mut Section,
+        {
+            let mut section = Section::new();
+            func(&mut section);
+            self.push_layout(LayoutComponent::Section(section));
+            self
+        }
+
+        pub fn media_gallery<Func>(&mut self, func: Func) -> &mut Self
+        where
+            Func: FnOnce(&mut MediaGallery) -> &mut MediaGallery,
+        {
+            let mut gallery = MediaGallery::new();
+            func(&mut gallery);
+            self.push_layout(LayoutComponent::MediaGallery(gallery));
+            self
+        }
+
+        /// Adds vertical space, optionally with a dividing line.
+        pub fn separator<Func>(&mut self, func: Func) -> &mut Self
+        where
+            Func: FnOnce(&mut Separator) -> &mut Separator,
+        {
+            let mut separator = Separator::new();
+            func(&mut separator);
+            self.push_layout(LayoutComponent::Separator(separator));
+            self
+        }
     };
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Webhook {
     pub id: Snowflake,
     #[serde(rename = "type")]
-    pub webhook_type: i8,
-    pub guild_id: Snowflake,
+    pub webhook_type: WebhookType,
+    pub guild_id: Option<Snowflake>,
     pub channel_id: Snowflake,
     pub name: Option<String>,
     pub avatar: Option<String>,
-    pub token: String,
+    /// `None` for webhooks without a token, e.g. channel follower webhooks.
+    pub token: Option<String>,
     pub application_id: Option<Snowflake>,
+    /// The guild of the followed announcement channel, for channel follower webhooks.
+    pub source_guild: Option<WebhookSourceGuild>,
+    /// The followed announcement channel, for channel follower webhooks.
+    pub source_channel: Option<WebhookSourceChannel>,
+    /// The URL to execute the webhook, only returned for webhooks created through OAuth2.
+    pub url: Option<String>,
+}
+
+/// The guild a channel follower webhook receives messages from.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebhookSourceGuild {
+    pub id: Snowflake,
+    pub name: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// The announcement channel a channel follower webhook receives messages from.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebhookSourceChannel {
+    pub id: Snowflake,
+    pub name: Option<String>,
+}
+
+/// The kind of a webhook.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "i8", into = "i8")]
+#[non_exhaustive]
+pub enum WebhookType {
+    /// Posts messages to a channel with a token.
+    Incoming,
+    /// Posts messages published in a followed announcement channel.
+    ChannelFollower,
+    /// Used with interactions.
+    Application,
+    /// A type this version of the crate does not know yet.
+    Unknown(i8),
+}
+
+impl From<i8> for WebhookType {
+    fn from(value: i8) -> Self {
+        match value {
+            1 => WebhookType::Incoming,
+            2 => WebhookType::ChannelFollower,
+            3 => WebhookType::Application,
+            other => WebhookType::Unknown(other),
+        }
+    }
+}
+
+impl From<WebhookType> for i8 {
+    fn from(webhook_type: WebhookType) -> Self {
+        match webhook_type {
+            WebhookType::Incoming => 1,
+            WebhookType::ChannelFollower => 2,
+            WebhookType::Application => 3,
+            WebhookType::Unknown(other) => other,
+        }
+    }
+}
+
+/// The settings of a webhook to create or modify. Fields that are not set are left unchanged.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookEdit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avatar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) channel_id: Option<Snowflake>,
+    /// Sent in the `X-Audit-Log-Reason` header rather than the body.
+    #[serde(skip)]
+    pub(crate) audit_log_reason: Option<String>,
+}
+
+impl WebhookEdit {
+    interval_member!(NAME_LEN_INTERVAL, usize, 1, 80);
+    interval_member!(AUDIT_LOG_REASON_LEN_INTERVAL, usize, 1, 512);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn avatar(&mut self, avatar: &Avatar) -> &mut Self {
+        self.avatar = Some(avatar.data_uri().to_owned());
+        self
+    }
+
+    /// Moves the webhook to another channel of the same guild.
+    pub fn channel_id<Id: Into<Snowflake>>(&mut self, channel_id: Id) -> &mut Self {
+        self.channel_id = Some(channel_id.into());
+        self
+    }
+
+    /// The reason for the change, shown in the audit log of the guild.
+    pub fn audit_log_reason(&mut self, reason: impl Into<String>) -> &mut Self {
+        self.audit_log_reason = Some(reason.into());
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationReport> {
+        let mut context = MessageContext::new();
+        if let Some(name) = &self.name {
+            context.within("name".to_string(), |context| check_webhook_name(name, "Webhook name", context));
+        }
+        if let Some(reason) = &self.audit_log_reason {
+            context.check(interval_check(
+                &Self::AUDIT_LOG_REASON_LEN_INTERVAL,
+                &char_count(reason),
+                "Audit log reason length",
+            ));
+        }
+        context.into_result()
+    }
 }
 
+/// Words Discord does not allow in webhook names, in any case.
+const RESERVED_NAME_WORDS: [&str; 2] = ["clyde", "discord"];
+
+/// Checks a webhook name or message username against Discord's rules: 1 to 80 characters after
+/// trimming whitespace, without `RESERVED_NAME_WORDS`.
+fn check_webhook_name(name: &str, label: &str, context: &mut MessageContext) {
+    context.check(interval_check(
+        &WebhookEdit::NAME_LEN_INTERVAL,
+        &char_count(name.trim()),
+        &format!("{} length", label),
+    ));
+    let lowercase = name.to_lowercase();
+    for word in RESERVED_NAME_WORDS.iter().filter(|word| lowercase.contains(*word)) {
+        context.report(format!("{} cannot contain \"{}\"!", label, word));
+    }
+}
+
+/// A violation of a Discord API constraint found while validating a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    location: String,
+    message: String,
+}
+
+impl ValidationError {
+    /// Where in the message payload the violation was found, e.g. `embeds[1].fields[3]`.
+    /// Empty for violations of the message itself.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.location.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.location, self.message)
+        }
+    }
+}
+
+/// Every violation found while validating a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    errors: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors: Vec<String> = self.errors.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", errors.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
 #[derive(Debug)]
 pub(crate) struct MessageContext {
     custom_ids: HashSet<String>,
     embeds_character_counter: usize,
-    button_count_in_action_row: usize,
+    text_display_character_counter: usize,
+    /// The kinds of the components registered in the current action row.
+    row_components: Vec<RowComponent>,
+    location: Vec<String>,
+    errors: Vec<ValidationError>,
+}
+
+/// The length of a text as Discord counts it: in characters, not bytes.
+fn char_count(text: &str) -> usize {
+    text.chars().count()
 }
 
 fn interval_check<T: Ord + Display>(
@@ -61,6 +406,33 @@ fn interval_check<T: Ord + Display>(
     Ok(())
 }
 
+/// Schemes of links, e.g. the url of an embed.
+const LINK_SCHEMES: [&str; 2] = ["http", "https"];
+/// Schemes of link buttons, which may also open Discord itself.
+const BUTTON_SCHEMES: [&str; 3] = ["http", "https", "discord"];
+/// Schemes of images and other media; `attachment://<filename>` refers to an uploaded file.
+const MEDIA_SCHEMES: [&str; 3] = ["http", "https", "attachment"];
+
+fn url_check(url: &str, schemes: &[&str], field_name: &str) -> Result<(), String> {
+    let valid = match url.split_once("://") {
+        Some((scheme, rest)) => {
+            schemes.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+                && !rest.is_empty()
+                && !url.chars().any(char::is_whitespace)
+        }
+        None => false,
+    };
+    if !valid {
+        return Err(format!(
+            "{} ({:?}) is not a URL with one of the schemes {}",
+            field_name,
+            url,
+            schemes.join(", ")
+        ));
+    }
+    Ok(())
+}
+
 impl MessageContext {
     /// Tries to register a custom id.
     ///
@@ -73,82 +445,158 @@ impl MessageContext {
     /// * `id`: the custom id to be registered
     ///
     ///
-    /// # Return value
-    /// Error variant contains an error message
-    fn register_custom_id(&mut self, id: &str) -> Result<(), String> {
-        interval_check(
+    /// # Violations
+    /// Recorded in the context
+    fn register_custom_id(&mut self, id: &str) {
+        self.check(interval_check(
             &Message::CUSTOM_ID_LEN_INTERVAL,
-            &id.len(),
-            "Custom ID length")?;
+            &char_count(id),
+            "Custom ID length"));
 
         if !self.custom_ids.insert(id.to_string()) {
-            return Err(format!("Attempt to use the same custom ID ({}) twice!", id));
+            self.report(format!("Attempt to use the same custom ID ({}) twice!", id));
         }
-        Ok(())
     }
 
     /// Tries to register an Embed
     ///
-    /// # Return value
-    ///
-    /// None on no error. Some(String) containing the reason for failure.
-    pub fn register_embed(&mut self, embed: &Embed) -> Result<(), String> {
-
-        self.embeds_character_counter += embed.title.as_ref().map_or(0, |s| s.len());
-        self.embeds_character_counter += embed.description.as_ref().map_or(0, |s| s.len());
-        self.embeds_character_counter += embed.footer.as_ref().map_or(0, |f| f.text.len());
-        self.embeds_character_counter += embed.author.as_ref().map_or(0, |a| a.name.len());
+    /// # Violations
+    /// Recorded in the context
+    fn register_embed(&mut self, embed: &Embed) {
 
-        embed.fields.iter().for_each(|f| {
-            self.embeds_character_counter += f.name.len() + f.value.len();
-        });
+        self.embeds_character_counter += embed.char_count();
 
-        interval_check(
+        self.check(interval_check(
             &Message::EMBED_TOTAL_TEXT_LEN_INTERVAL,
             &self.embeds_character_counter,
-            "Character count across all embeds")?;
-        Ok(())
+            "Character count across all embeds"));
+    }
+
+    /// Tries to register the text of a text display
+    ///
+    /// # Violations
+    /// Recorded in the context
+    fn register_text_display(&mut self, content: &str) {
+        self.text_display_character_counter += char_count(content);
+
+        self.check(interval_check(
+            &Message::TEXT_DISPLAY_TOTAL_LEN_INTERVAL,
+            &self.text_display_character_counter,
+            "Character count across all text displays"));
     }
 
     pub(crate) fn new() -> MessageContext {
         MessageContext {
             custom_ids: HashSet::new(),
-            button_count_in_action_row: 0,
-            embeds_character_counter: 0
+            row_components: vec![],
+            embeds_character_counter: 0,
+            text_display_character_counter: 0,
+            location: vec![],
+            errors: vec![],
+        }
+    }
+
+    /// Records a violation at the current location.
+    fn report(&mut self, message: String) {
+        self.errors.push(ValidationError {
+            location: self.location.join("."),
+            message,
+        });
+    }
+
+    /// Records the violation of a failed check.
+    fn check(&mut self, result: Result<(), String>) {
+        if let Err(message) = result {
+            self.report(message);
+        }
+    }
+
+    /// Checks a nested part of the message, e.g. `within("embeds[0]", ...)`.
+    /// Violations found by `check` are reported at that location.
+    fn within<Check>(&mut self, location: String, check: Check)
+    where
+        Check: FnOnce(&mut Self),
+    {
+        self.location.push(location);
+        check(self);
+        self.location.pop();
+    }
+
+    /// All recorded violations. Ok if there were none.
+    pub(crate) fn into_result(self) -> Result<(), ValidationReport> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationReport {
+                errors: self.errors,
+            })
         }
     }
 
     /// Tries to register a button using the button's custom id.
     ///
-    /// # Return value
-    /// Error variant contains an error message
+    /// # Violations
+    /// Recorded in the context
     ///
     /// # Note
-    /// Subsequent calls register other components semantically in the same action row.
-    /// To register components in a new action row, use the `register_action_row` function before
-    /// calling this function
-    fn register_button(&mut self, id: &str) -> Result<(), String> {
-        self.register_custom_id(id)?;
-        self.button_count_in_action_row += 1;
-
-        interval_check(
-            &ActionRow::BUTTON_COUNT_INTERVAL,
-            &self.button_count_in_action_row,
-            "Button count")?;
-        Ok(())
+    /// The button is registered in the action row checked by the enclosing `within_action_row`.
+    fn register_button(&mut self, id: &str) {
+        self.register_custom_id(id);
+        self.row_components.push(RowComponent::Button);
     }
-    /// Switches the context to register components logically in a "new" action row.
+
+    /// Tries to register a select menu using the menu's custom id.
     ///
-    /// # Watch out!
-    /// This function shall be called only once per one action row. (due to the lack of action row
-    /// identification)
-    fn register_action_row(&mut self) {
-        self.button_count_in_action_row = 0;
-        self.button_count_in_action_row = 0;
+    /// # Violations
+    /// Recorded in the context
+    ///
+    /// # Note
+    /// Same as `register_button`, the select menu is registered in the current action row.
+    fn register_select_menu(&mut self, id: &str) {
+        self.register_custom_id(id);
+        self.row_components.push(RowComponent::SelectMenu);
+    }
+
+    /// Checks the components of an action row. The buttons and select menus registered by
+    /// `check` make up the row, which must hold either up to five buttons or a single select
+    /// menu.
+    ///
+    /// # Violations
+    /// Recorded in the context
+    fn within_action_row<Check>(&mut self, check: Check)
+    where
+        Check: FnOnce(&mut Self),
+    {
+        let outer_row = std::mem::take(&mut self.row_components);
+        check(self);
+        let row = std::mem::replace(&mut self.row_components, outer_row);
+
+        let count = |kind: RowComponent| row.iter().filter(|component| **component == kind).count();
+        let buttons = count(RowComponent::Button);
+        let select_menus = count(RowComponent::SelectMenu);
+        if buttons > 0 && select_menus > 0 {
+            self.report("An action row cannot contain both buttons and a select menu!".to_string());
+        }
+        self.check(interval_check(
+            &ActionRow::BUTTON_COUNT_INTERVAL,
+            &buttons,
+            "Button count"));
+        self.check(interval_check(
+            &ActionRow::SELECT_MENU_COUNT_INTERVAL,
+            &select_menus,
+            "Select menu count"));
     }
 }
 
-#[derive(Serialize, Debug)]
+/// The kind of a component in an action row, for the row composition rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowComponent {
+    Button,
+    SelectMenu,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "MessagePayload")]
 pub struct Message {
     pub content: Option<String>,
     pub username: Option<String>,
@@ -156,8 +604,229 @@ pub struct Message {
     pub tts: bool,
     pub embeds: Vec<Embed>,
     pub allow_mentions: Option<AllowedMentions>,
-    #[serde(rename = "components")]
     pub action_rows: Vec<ActionRow>,
+    /// The components of a components v2 message, in order. Sent after the `action_rows`.
+    pub layout: Vec<LayoutComponent>,
+    /// Message flags, like `Message::SUPPRESS_NOTIFICATIONS`.
+    pub flags: u64,
+    /// The files uploaded with the message, see `Message::attachment`.
+    pub attachments: Vec<Attachment>,
+    /// The maximum size of all uploaded files together in bytes, see `Message::upload_limit`.
+    /// Only used for validation, it is not sent.
+    pub upload_limit: u64,
+    /// Top-level payload fields this crate does not model (yet), see `Message::extra`.
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The payload a `Message` is sent as: action rows and layout components share the
+/// `components` array.
+#[derive(Serialize)]
+struct MessagePayloadRef<'a> {
+    content: &'a Option<String>,
+    username: &'a Option<String>,
+    avatar_url: &'a Option<String>,
+    tts: bool,
+    embeds: &'a [Embed],
+    allowed_mentions: &'a Option<AllowedMentions>,
+    components: Components<'a>,
+    #[serde(skip_serializing_if = "is_zero")]
+    flags: u64,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    attachments: &'a [Attachment],
+    #[serde(flatten)]
+    extra: &'a serde_json::Map<String, serde_json::Value>,
+}
+
+/// The action rows followed by the layout components.
+struct Components<'a>(&'a [ActionRow], &'a [LayoutComponent]);
+
+impl Serialize for Components<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut components = serializer.serialize_seq(Some(self.0.len() + self.1.len()))?;
+        for row in self.0 {
+            components.serialize_element(row)?;
+        }
+        for component in self.1 {
+            components.serialize_element(component)?;
+        }
+        components.end()
+    }
+}
+
+fn is_zero(flags: &u64) -> bool {
+    *flags == 0
+}
+
+impl Serialize for Message {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        MessagePayloadRef {
+            content: &self.content,
+            username: &self.username,
+            avatar_url: &self.avatar_url,
+            tts: self.tts,
+            embeds: &self.embeds,
+            allowed_mentions: &self.allow_mentions,
+            components: Components(&self.action_rows, &self.layout),
+            flags: self.flags,
+            attachments: &self.attachments,
+            extra: &self.extra,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct MessagePayload {
+    content: Option<String>,
+    username: Option<String>,
+    avatar_url: Option<String>,
+    tts: bool,
+    embeds: Vec<Embed>,
+    allowed_mentions: Option<AllowedMentions>,
+    components: Vec<LayoutComponent>,
+    flags: u64,
+    attachments: Vec<Attachment>,
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<MessagePayload> for Message {
+    fn from(payload: MessagePayload) -> Self {
+        let components_v2 = payload.flags & Message::IS_COMPONENTS_V2 != 0
+            || payload
+                .components
+                .iter()
+                .any(|component| !matches!(component, LayoutComponent::ActionRow(_)));
+        let (action_rows, layout) = if components_v2 {
+            (vec![], payload.components)
+        } else {
+            let rows = payload
+                .components
+                .into_iter()
+                .filter_map(|component| match component {
+                    LayoutComponent::ActionRow(row) => Some(row),
+                    _ => None,
+                })
+                .collect();
+            (rows, vec![])
+        };
+
+        Message {
+            content: payload.content,
+            username: payload.username,
+            avatar_url: payload.avatar_url,
+            tts: payload.tts,
+            embeds: payload.embeds,
+            allow_mentions: payload.allowed_mentions,
+            action_rows,
+            layout,
+            flags: payload.flags,
+            attachments: payload.attachments,
+            upload_limit: Message::DEFAULT_UPLOAD_LIMIT,
+            extra: payload.extra,
+        }
+    }
+}
+
+/// A type with a Discord message representation, sendable with `WebhookClient::send_item`.
+///
+/// Implemented for messages, text (sent as content) and everything that implements
+/// `IntoEmbed`.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # struct BuildResult { number: u32, commit: String }
+/// # impl BuildResult {
+/// #     fn color(&self) -> Color { Color::GREEN }
+/// # }
+/// impl IntoMessage for BuildResult {
+///     fn into_message(self) -> Message {
+///         let mut message = Message::new();
+///         message
+///             .content(&format!("Build #{} finished", self.number))
+///             .embed(|embed| embed.title(&self.commit).color(self.color()));
+///         message
+///     }
+/// }
+///
+/// # async fn run(client: WebhookClient, build_result: BuildResult) -> WebhookResult<()> {
+/// client.send_item(build_result).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait IntoMessage {
+    fn into_message(self) -> Message;
+}
+
+/// A type that is shown as a single embed, see `Message::add_embed`.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # struct Alert { name: String }
+/// impl IntoEmbed for &Alert {
+///     fn into_embed(self) -> Embed {
+///         let mut embed = Embed::new();
+///         embed.title(&self.name).color(Color::RED);
+///         embed
+///     }
+/// }
+///
+/// # async fn run(client: WebhookClient, alert: Alert) -> WebhookResult<()> {
+/// client.send_item(&alert).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait IntoEmbed {
+    fn into_embed(self) -> Embed;
+}
+
+impl IntoMessage for Message {
+    fn into_message(self) -> Message {
+        self
+    }
+}
+
+impl IntoMessage for &str {
+    fn into_message(self) -> Message {
+        let mut message = Message::new();
+        message.content(self);
+        message
+    }
+}
+
+impl IntoMessage for String {
+    fn into_message(self) -> Message {
+        self.as_str().into_message()
+    }
+}
+
+impl<Item: IntoEmbed> IntoMessage for Item {
+    fn into_message(self) -> Message {
+        let mut message = Message::new();
+        message.add_embed(self);
+        message
+    }
+}
+
+impl IntoEmbed for Embed {
+    fn into_embed(self) -> Embed {
+        self
+    }
+}
+
+impl Default for Message {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Message {
@@ -170,21 +839,26 @@ impl Message {
             embeds: vec![],
             allow_mentions: None,
             action_rows: vec![],
+            layout: vec![],
+            flags: 0,
+            attachments: vec![],
+            upload_limit: Self::DEFAULT_UPLOAD_LIMIT,
+            extra: serde_json::Map::new(),
         }
     }
 
-    pub fn content(&mut self, content: &str) -> &mut Self {
-        self.content = Some(content.to_owned());
+    pub fn content(&mut self, content: impl Into<String>) -> &mut Self {
+        self.content = Some(content.into());
         self
     }
 
-    pub fn username(&mut self, username: &str) -> &mut Self {
-        self.username = Some(username.to_owned());
+    pub fn username(&mut self, username: impl Into<String>) -> &mut Self {
+        self.username = Some(username.into());
         self
     }
 
-    pub fn avatar_url(&mut self, avatar_url: &str) -> &mut Self {
-        self.avatar_url = Some(avatar_url.to_owned());
+    pub fn avatar_url(&mut self, avatar_url: impl Into<String>) -> &mut Self {
+        self.avatar_url = Some(avatar_url.into());
         self
     }
 
@@ -195,7 +869,7 @@ impl Message {
 
     pub fn embed<Func>(&mut self, func: Func) -> &mut Self
     where
-        Func: Fn(&mut Embed) -> &mut Embed,
+        Func: FnOnce(&mut Embed) -> &mut Embed,
     {
         let mut embed = Embed::new();
         func(&mut embed);
@@ -204,17 +878,75 @@ impl Message {
         self
     }
 
+    /// Adds an embed built elsewhere, see `IntoEmbed`.
+    pub fn add_embed<Item: IntoEmbed>(&mut self, embed: Item) -> &mut Self {
+        self.embeds.push(embed.into_embed());
+        self
+    }
+
+    /// Adds an action row. In a components v2 message the row is added to the `layout`,
+    /// after the components added so far.
     pub fn action_row<Func>(&mut self, func: Func) -> &mut Self
     where
-        Func: Fn(&mut ActionRow) -> &mut ActionRow,
+        Func: FnOnce(&mut ActionRow) -> &mut ActionRow,
     {
         let mut row = ActionRow::new();
         func(&mut row);
-        self.action_rows.push(row);
+        if self.flags & Self::IS_COMPONENTS_V2 != 0 {
+            self.layout.push(LayoutComponent::ActionRow(row));
+        } else {
+            self.action_rows.push(row);
+        }
+
+        self
+    }
+
+    /// Replaces the message flags, e.g. `Message::SUPPRESS_EMBEDS | Message::SUPPRESS_NOTIFICATIONS`.
+    /// The layout builders set `IS_COMPONENTS_V2` themselves.
+    pub fn flags(&mut self, flags: u64) -> &mut Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Adds a container to the layout of a components v2 message, see `Container`.
+    pub fn container<Func>(&mut self, func: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut Container) -> &mut Container,
+    {
+        let mut container = Container::new();
+        func(&mut container);
+        self.push_layout(LayoutComponent::Container(container));
+        self
+    }
+
+    // The layout builders turn the message into a components v2 message, which cannot have
+    // content or embeds.
+    layout_builders!();
+
+    fn push_layout(&mut self, component: LayoutComponent) {
+        self.flags |= Self::IS_COMPONENTS_V2;
+        self.layout.push(component);
+    }
 
+    /// Adds a top-level field to the payload as it is, for payload fields Discord supports
+    /// but this crate does not model yet. Setting a field twice replaces the earlier value.
+    ///
+    /// Fields the crate models (like `content` or `embeds`) cannot be set this way;
+    /// `validate` rejects them.
+    ///
+    /// Example
+    /// ```no_run
+    /// # use serde_json::json;
+    /// # let mut message = webhook::models::Message::new();
+    /// message.content("New release").extra("thread_name", json!("Release 1.2"));
+    /// ```
+    pub fn extra(&mut self, key: impl Into<String>, value: serde_json::Value) -> &mut Self {
+        self.extra.insert(key.into(), value);
         self
     }
 
+    interval_member!(CONTENT_LEN_INTERVAL, usize, 0, 2000);
+    interval_member!(EMBED_COUNT_INTERVAL, usize, 0, 10);
     interval_member!(ACTION_ROW_COUNT_INTERVAL, usize, 0, 5);
     interval_member!(LABEL_LEN_INTERVAL, usize, 0, 80);
     interval_member!(CUSTOM_ID_LEN_INTERVAL, usize, 1, 100);
@@ -223,6 +955,60 @@ impl Message {
     // must not exceed 6000 characters.
     interval_member!(EMBED_TOTAL_TEXT_LEN_INTERVAL, usize, 0, 6000);
 
+    interval_member!(COMPONENTS_V2_COUNT_INTERVAL, usize, 1, 40);
+    interval_member!(ATTACHMENT_COUNT_INTERVAL, usize, 0, 10);
+    interval_member!(MEDIA_DESCRIPTION_LEN_INTERVAL, usize, 0, 1024);
+    interval_member!(TEXT_DISPLAY_TOTAL_LEN_INTERVAL, usize, 0, 4000);
+
+    /// Hides the embeds of links in the content.
+    pub const SUPPRESS_EMBEDS: u64 = 1 << 2;
+    /// Sends the message without push and desktop notifications.
+    pub const SUPPRESS_NOTIFICATIONS: u64 = 1 << 12;
+    /// Lays out the message with `layout` components instead of content and embeds.
+    pub const IS_COMPONENTS_V2: u64 = 1 << 15;
+
+    /// The upload limit of servers without boosts.
+    pub const DEFAULT_UPLOAD_LIMIT: u64 = 8 * 1024 * 1024;
+
+    /// The payload fields serialized from the fields of `Message`.
+    const MODELED_FIELDS: [&'static str; 9] = [
+        "content",
+        "username",
+        "avatar_url",
+        "tts",
+        "embeds",
+        "allowed_mentions",
+        "components",
+        "flags",
+        "attachments",
+    ];
+
+    /// Checks the message against the Discord API constraints, collecting every violation.
+    pub fn validate(&self) -> Result<(), ValidationReport> {
+        let mut context = MessageContext::new();
+        self.check_compatibility(&mut context);
+        context.into_result()
+    }
+
+    /// Checks for likely mistakes that Discord accepts and `validate` does not report: content
+    /// mentioning `@everyone`, `@here` or a role while `allowed_mentions` is not set, so the
+    /// mention pings. Clients run it with `WebhookClientBuilder::lints`.
+    pub fn lint(&self) -> Result<(), ValidationReport> {
+        let mut context = MessageContext::new();
+        if let (Some(content), None) = (self.content.as_ref(), self.allow_mentions.as_ref()) {
+            context.within("content".to_string(), |context| {
+                for mention in mass_mentions(content) {
+                    context.report(format!(
+                        "{} pings because allowed_mentions is not set! Allow it with \
+                         Message::allowed_mentions or use Message::suppress_mentions",
+                        mention
+                    ));
+                }
+            });
+        }
+        context.into_result()
+    }
+
     pub fn allow_mentions(
         &mut self,
         parse: Option<Vec<AllowedMention>>,
@@ -233,18 +1019,575 @@ impl Message {
         self.allow_mentions = Some(AllowedMentions::new(parse, roles, users, replied_user));
         self
     }
-}
 
-#[derive(Serialize, Debug)]
-pub struct Embed {
-    pub title: Option<String>,
-    #[serde(rename = "type")]
-    embed_type: String,
-    pub description: Option<String>,
-    pub url: Option<String>,
-    // ISO8601,
-    pub timestamp: Option<String>,
-    pub color: Option<String>,
+    /// Configures which mentions in the content may ping. Nothing pings unless allowed.
+    ///
+    /// Example
+    /// ```no_run
+    /// # let mut message = webhook::models::Message::new();
+    /// message
+    ///     .content("<@123> <@&456> @everyone")
+    ///     .allowed_mentions(|mentions| mentions.users([123]).everyone());
+    /// ```
+    pub fn allowed_mentions<Func>(&mut self, func: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut AllowedMentions) -> &mut AllowedMentions,
+    {
+        let mut allowed_mentions = AllowedMentions::none();
+        func(&mut allowed_mentions);
+        self.allow_mentions = Some(allowed_mentions);
+        self
+    }
+
+    /// Uploads `data` as a file named `filename` with the message.
+    ///
+    /// Images can be shown in embeds and media components as `attachment://<filename>`.
+    ///
+    /// Example
+    /// ```no_run
+    /// # let mut message = webhook::models::Message::new();
+    /// # let png_bytes = std::fs::read("graph.png").unwrap();
+    /// message
+    ///     .attachment("graph.png", png_bytes, |attachment| attachment.description("CPU usage"))
+    ///     .embed(|embed| embed.image("attachment://graph.png"));
+    /// ```
+    pub fn attachment<Func>(
+        &mut self,
+        filename: impl Into<String>,
+        data: Vec<u8>,
+        func: Func,
+    ) -> &mut Self
+    where
+        Func: FnOnce(&mut Attachment) -> &mut Attachment,
+    {
+        let mut attachment = Attachment::new(self.next_upload_id(), filename, Some(data));
+        func(&mut attachment);
+        self.attachments.push(attachment);
+        self
+    }
+
+    /// Uploads the file at `url`, which the client downloads right before sending the message.
+    ///
+    /// For mirroring files from hosts Discord cannot reach. The file is named after the last
+    /// segment of the URL path; downloads larger than the client's `max_download_size` fail
+    /// the send. The download is a plain `GET`, without the client's default headers and
+    /// middlewares. `webhook::request::build_request` cannot download and rejects such messages.
+    ///
+    /// Example
+    /// ```no_run
+    /// # let mut message = webhook::models::Message::new();
+    /// message
+    ///     .attachment_from_url("http://grafana.internal/render/cpu.png", |attachment| attachment)
+    ///     .embed(|embed| embed.image("attachment://cpu.png"));
+    /// ```
+    pub fn attachment_from_url<Func>(&mut self, url: impl Into<String>, func: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut Attachment) -> &mut Attachment,
+    {
+        let url = url.into();
+        let without_query = url.split(['?', '#']).next().unwrap_or_default();
+        let without_scheme = without_query.splitn(2, "://").last().unwrap_or_default();
+        let filename = match without_scheme.split_once('/') {
+            Some((_, path)) if !path.is_empty() && !path.ends_with('/') => path.rsplit('/').next().unwrap_or("file"),
+            _ => "file",
+        };
+        let mut attachment = Attachment::new(self.next_upload_id(), filename, None);
+        attachment.source_url = Some(url);
+        func(&mut attachment);
+        self.attachments.push(attachment);
+        self
+    }
+
+    /// New files are referred to by their index among the uploads.
+    fn next_upload_id(&self) -> Snowflake {
+        Snowflake::new(self.attachments.iter().filter(|attachment| attachment.is_upload()).count() as u64)
+    }
+
+    /// Raises (or lowers) the size the uploaded files may have together, e.g. to the limit of
+    /// a boosted server. `Message::DEFAULT_UPLOAD_LIMIT` by default.
+    pub fn upload_limit(&mut self, upload_limit: u64) -> &mut Self {
+        self.upload_limit = upload_limit;
+        self
+    }
+
+    /// Prevents any mention in the content from pinging anyone.
+    pub fn suppress_mentions(&mut self) -> &mut Self {
+        self.allow_mentions = Some(AllowedMentions::none());
+        self
+    }
+
+    /// The JSON payload the message is sent as.
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("a message always serializes to JSON")
+    }
+
+    /// Reads a message from a JSON payload. Fields the crate does not model end up in `extra`.
+    pub fn from_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(value)
+    }
+
+    /// The payload of the message as pretty-printed JSON with the keys of every object in
+    /// alphabetical order and without `null` fields, so the same message always gives the same
+    /// text, e.g. for snapshot tests.
+    pub fn to_canonical_json(&self) -> String {
+        let value = canonicalize(self.to_value());
+        serde_json::to_string_pretty(&value).expect("a JSON value always serializes")
+    }
+
+    /// A `curl` command sending the message to the webhook at `url`, with the token of the
+    /// webhook masked, to check whether Discord accepts a payload outside of this crate.
+    ///
+    /// Files to upload are referred to by their file name, relative to the working directory
+    /// of the shell.
+    ///
+    /// Example
+    /// ```no_run
+    /// # let message = webhook::models::Message::new();
+    /// # let url = "https://discord.com/api/webhooks/123/token";
+    /// println!("{}", message.to_curl(&url));
+    /// // curl -X POST 'https://discord.com/api/webhooks/123/***' -H 'Content-Type: application/json' --data-raw '{...}'
+    /// ```
+    pub fn to_curl(&self, url: &str) -> String {
+        curl_command("POST", url, &self.to_value(), &self.attachments)
+    }
+
+    /// Applies `patch` to the payload of the message as a JSON merge patch (RFC 7396):
+    /// objects are merged recursively, `null` removes a field and other values replace it.
+    ///
+    /// Example
+    /// ```no_run
+    /// # use serde_json::json;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// # let mut message = webhook::models::Message::new();
+    /// message.merge_value(json!({"flags": 4096, "embeds": [{"title": "Replaced"}]}))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The contents and source URLs of the files stay with the attachments of the same id, and
+    /// the upload limit is kept; neither is part of the payload.
+    pub fn merge_value(&mut self, patch: serde_json::Value) -> Result<&mut Self, serde_json::Error> {
+        let mut value = self.to_value();
+        merge_patch(&mut value, patch);
+        let mut merged = Self::from_value(value)?;
+        merged.upload_limit = self.upload_limit;
+        for attachment in merged.attachments.iter_mut() {
+            let previous = self
+                .attachments
+                .iter_mut()
+                .find(|previous| previous.id == attachment.id);
+            if let Some(previous) = previous {
+                attachment.data = previous.data.take();
+                attachment.source_url = previous.source_url.take();
+            }
+        }
+        *self = merged;
+        Ok(self)
+    }
+
+    /// Splits a message whose content is too long into several messages, cutting the content
+    /// at line breaks where possible (see `fmt::split_text`).
+    ///
+    /// Every part keeps the username, avatar, tts flag, allowed mentions and flags. The embeds,
+    /// components, attachments and extra fields are attached to the last part. A message whose content
+    /// fits is returned as is.
+    pub fn split_content(mut self) -> Vec<Message> {
+        let content = match self.content.take() {
+            Some(content) if char_count(&content) > Self::CONTENT_LEN_INTERVAL.max_allowed => content,
+            content => {
+                self.content = content;
+                return vec![self];
+            }
+        };
+
+        let mut chunks = split_text(&content, Self::CONTENT_LEN_INTERVAL.max_allowed);
+        let last_chunk = chunks.pop();
+        let mut parts: Vec<Message> = chunks
+            .into_iter()
+            .map(|chunk| Message {
+                content: Some(chunk),
+                username: self.username.clone(),
+                avatar_url: self.avatar_url.clone(),
+                tts: self.tts,
+                embeds: vec![],
+                allow_mentions: self.allow_mentions.clone(),
+                action_rows: vec![],
+                layout: vec![],
+                flags: self.flags,
+                attachments: vec![],
+                upload_limit: self.upload_limit,
+                extra: serde_json::Map::new(),
+            })
+            .collect();
+        self.content = last_chunk;
+        parts.push(self);
+        parts
+    }
+
+    /// The characters of all embeds that count towards `EMBED_TOTAL_TEXT_LEN_INTERVAL`.
+    pub fn embed_char_count(&self) -> usize {
+        self.embeds.iter().map(Embed::char_count).sum()
+    }
+
+    /// How many more embed characters the message can take before it exceeds the total limit
+    /// across its embeds. Together with `embed_slots_remaining` this tells when data streamed
+    /// into embeds needs a new message.
+    ///
+    /// Example
+    /// ```no_run
+    /// # use webhook::prelude::*;
+    /// # async fn run(client: WebhookClient, mut message: Message, embed: Embed) -> WebhookResult<()> {
+    /// if embed.char_count() > message.remaining_embed_chars() || message.embed_slots_remaining() == 0 {
+    ///     client.send_message(&message).await?;
+    ///     message = Message::new();
+    /// }
+    /// message.add_embed(embed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remaining_embed_chars(&self) -> usize {
+        Self::EMBED_TOTAL_TEXT_LEN_INTERVAL
+            .max_allowed
+            .saturating_sub(self.embed_char_count())
+    }
+
+    /// How many more embeds the message can take.
+    pub fn embed_slots_remaining(&self) -> usize {
+        Self::EMBED_COUNT_INTERVAL.max_allowed.saturating_sub(self.embeds.len())
+    }
+
+    /// Shortens the content and the embed texts that exceed Discord's limits, ending them with
+    /// `…`, so text of unknown length (e.g. forwarded logs) does not fail validation.
+    ///
+    /// If the embeds together still exceed the total limit, the descriptions and then the field
+    /// values are shortened further, starting with the last embed. Counts are not changed.
+    pub fn truncate_to_limits(&mut self) -> &mut Self {
+        if let Some(content) = self.content.as_mut() {
+            truncate_in_place(content, Self::CONTENT_LEN_INTERVAL.max_allowed);
+        }
+        for embed in self.embeds.iter_mut() {
+            embed.truncate_to_limits();
+        }
+
+        let total = self.embed_char_count();
+        let mut excess = total.saturating_sub(Self::EMBED_TOTAL_TEXT_LEN_INTERVAL.max_allowed);
+        for embed in self.embeds.iter_mut().rev() {
+            if let Some(description) = embed.description.as_mut() {
+                shorten(description, &mut excess);
+            }
+        }
+        for embed in self.embeds.iter_mut().rev() {
+            for field in embed.fields.iter_mut().rev() {
+                shorten(&mut field.value, &mut excess);
+            }
+        }
+        self
+    }
+}
+
+fn truncate_in_place(text: &mut String, max_chars: usize) {
+    if char_count(text) > max_chars {
+        *text = truncate(text, max_chars);
+    }
+}
+
+/// Removes up to `excess` characters from `text`, keeping at least the `…`.
+fn shorten(text: &mut String, excess: &mut usize) {
+    let len = char_count(text);
+    if *excess == 0 || len <= 1 {
+        return;
+    }
+    let keep = len.saturating_sub(*excess).max(1);
+    *text = truncate(text, keep);
+    *excess -= len - keep;
+}
+
+/// The `@everyone`, `@here` and role mentions in `text`, which ping many users at once.
+fn mass_mentions(text: &str) -> Vec<&str> {
+    let mut mentions: Vec<&str> = ["@everyone", "@here"]
+        .iter()
+        .copied()
+        .filter(|mention| text.contains(mention))
+        .collect();
+    for (start, _) in text.match_indices("<@&") {
+        let rest = &text[start + "<@&".len()..];
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits > 0 && rest[digits..].starts_with('>') {
+            mentions.push(&text[start..start + "<@&".len() + digits + 1]);
+        }
+    }
+    mentions
+}
+
+/// Applies a JSON merge patch (RFC 7396) to `target`.
+fn merge_patch(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match patch {
+        serde_json::Value::Object(patch) => {
+            if !target.is_object() {
+                *target = serde_json::Value::Object(serde_json::Map::new());
+            }
+            let fields = target.as_object_mut().unwrap();
+            for (key, value) in patch {
+                if value.is_null() {
+                    fields.remove(&key);
+                } else {
+                    merge_patch(fields.entry(key).or_insert(serde_json::Value::Null), value);
+                }
+            }
+        }
+        patch => *target = patch,
+    }
+}
+
+/// A `curl` command sending `payload` and the files among `attachments` to `url`, see
+/// `Message::to_curl`.
+pub(crate) fn curl_command(
+    method: &str,
+    url: &str,
+    payload: &serde_json::Value,
+    attachments: &[Attachment],
+) -> String {
+    // single quotes keep everything literal in POSIX shells, except single quotes themselves
+    let quote = |text: &str| format!("'{}'", text.replace('\'', r"'\''"));
+    let mut command = format!("curl -X {} {}", method, quote(&redact_token(url)));
+    let uploads: Vec<&Attachment> = attachments.iter().filter(|attachment| attachment.is_upload()).collect();
+    if uploads.is_empty() {
+        command.push_str(" -H 'Content-Type: application/json' --data-raw ");
+        command.push_str(&quote(&payload.to_string()));
+    } else {
+        command.push_str(" --form-string ");
+        command.push_str(&quote(&format!("payload_json={}", payload)));
+        for attachment in uploads {
+            command.push_str(" -F ");
+            command.push_str(&quote(&format!("files[{}]=@\"{}\"", attachment.id, attachment.filename)));
+        }
+    }
+    command
+}
+
+/// Sorts the keys of every object in `value` and removes its `null` fields.
+fn canonicalize(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(fields) => {
+            // sorted explicitly, the map keeps insertion order with serde_json's `preserve_order`
+            let fields: BTreeMap<String, serde_json::Value> = fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, canonicalize(value)))
+                .collect();
+            serde_json::Value::Object(fields.into_iter().collect())
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(canonicalize).collect())
+        }
+        value => value,
+    }
+}
+
+/// An RGB color, serialized as the integer Discord expects.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u32);
+
+impl Color {
+    pub const BLACK: Color = Color(0x000000);
+    pub const WHITE: Color = Color(0xFFFFFF);
+    pub const RED: Color = Color(0xED4245);
+    pub const GREEN: Color = Color(0x57F287);
+    pub const YELLOW: Color = Color(0xFEE75C);
+    pub const FUCHSIA: Color = Color(0xEB459E);
+    pub const BLURPLE: Color = Color(0x5865F2);
+
+    pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        Color((red as u32) << 16 | (green as u32) << 8 | blue as u32)
+    }
+
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for Color {
+    fn from(value: u32) -> Self {
+        Color(value)
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((red, green, blue): (u8, u8, u8)) -> Self {
+        Color::from_rgb(red, green, blue)
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parses `#RRGGBB`, `0xRRGGBB` or `RRGGBB`.
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
+        let hex = color
+            .strip_prefix('#')
+            .or_else(|| color.strip_prefix("0x"))
+            .unwrap_or(color);
+        // from_str_radix would accept a sign, e.g. "+FFFFF"
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(format!("Color {:?} is not in the #RRGGBB format", color));
+        }
+        u32::from_str_radix(hex, 16)
+            .map(Color)
+            .map_err(|_| format!("Color {:?} is not in the #RRGGBB format", color))
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:06x}", self.0)
+    }
+}
+
+/// A point in time, serialized as the ISO8601 string Discord expects.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    /// Formats the time in UTC with millisecond precision.
+    fn from(time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds = since_epoch.as_secs();
+        let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+
+        // civil date from days since 1970-01-01, http://howardhinnant.github.io/date_algorithms.html
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let day_of_era = z % 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+        Timestamp(format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year,
+            month,
+            day,
+            seconds_of_day / 3600,
+            seconds_of_day / 60 % 60,
+            seconds_of_day % 60,
+            since_epoch.subsec_millis()
+        ))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for Timestamp
+where
+    Tz::Offset: Display,
+{
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        Timestamp(time.to_rfc3339())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for Timestamp {
+    fn from(time: time::OffsetDateTime) -> Self {
+        Timestamp(
+            time.format(&time::format_description::well_known::Rfc3339)
+                .expect("every OffsetDateTime with a four digit year is representable in RFC3339"),
+        )
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = String;
+
+    /// Accepts ISO8601 timestamps like `2024-01-31T12:00:00Z`, `2024-01-31T12:00:00.123+02:00`.
+    fn from_str(timestamp: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Timestamp {:?} is not an ISO8601 date and time", timestamp);
+        let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        // the parts below are sliced by bytes
+        if !timestamp.is_ascii() {
+            return Err(error());
+        }
+
+        let (date, time) = timestamp.split_once('T').ok_or_else(error)?;
+        let date_parts: Vec<&str> = date.split('-').collect();
+        if date_parts.len() != 3
+            || date_parts[0].len() != 4
+            || !date_parts.iter().all(|part| digits(part))
+        {
+            return Err(error());
+        }
+
+        let offset_start = time
+            .find(['Z', '+', '-'])
+            .ok_or_else(error)?;
+        let (clock, offset) = time.split_at(offset_start);
+        let (clock, fraction) = clock.split_once('.').unwrap_or((clock, "0"));
+        let clock_parts: Vec<&str> = clock.split(':').collect();
+        if clock_parts.len() != 3 || !clock_parts.iter().all(|part| digits(part)) || !digits(fraction) {
+            return Err(error());
+        }
+        let valid_offset = offset == "Z"
+            || (offset.len() == 6 && digits(&offset[1..3]) && &offset[3..4] == ":" && digits(&offset[4..]));
+        if !valid_offset {
+            return Err(error());
+        }
+
+        let number = |part: &str| part.parse::<u32>().map_err(|_| error());
+        let (year, month, day) = (
+            number(date_parts[0])?,
+            number(date_parts[1])?,
+            number(date_parts[2])?,
+        );
+        let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            2 if leap_year => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return Err(error()),
+        };
+        let in_range = (1..=days_in_month).contains(&day)
+            && number(clock_parts[0])? < 24
+            && number(clock_parts[1])? < 60
+            && number(clock_parts[2])? < 60
+            && (offset == "Z" || (number(&offset[1..3])? < 24 && number(&offset[4..])? < 60));
+        if !in_range {
+            return Err(error());
+        }
+
+        Ok(Timestamp(timestamp.to_owned()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Embed {
+    pub title: Option<String>,
+    #[serde(rename = "type")]
+    embed_type: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub timestamp: Option<Timestamp>,
+    pub color: Option<Color>,
     pub footer: Option<EmbedFooter>,
     pub image: Option<EmbedImage>,
     pub video: Option<EmbedVideo>,
@@ -254,6 +1597,12 @@ pub struct Embed {
     pub fields: Vec<EmbedField>,
 }
 
+impl Default for Embed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Embed {
     pub fn new() -> Self {
         Self {
@@ -273,93 +1622,214 @@ impl Embed {
         }
     }
 
-    pub fn title(&mut self, title: &str) -> &mut Self {
-        self.title = Some(title.to_owned());
+    /// A green embed titled `✅ <title>`, timestamped now.
+    ///
+    /// Example
+    /// ```no_run
+    /// # use webhook::models::{Embed, Message};
+    /// # let mut message = Message::new();
+    /// message.add_embed(Embed::success("Deployed", "billing 1.2.3 is live"));
+    /// ```
+    pub fn success(title: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::status(Color::GREEN, "✅", title.into(), description.into())
+    }
+
+    /// A yellow embed titled `⚠️ <title>`, timestamped now.
+    pub fn warning(title: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::status(Color::YELLOW, "⚠️", title.into(), description.into())
+    }
+
+    /// A red embed titled `❌ <title>`, timestamped now.
+    pub fn error(title: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::status(Color::RED, "❌", title.into(), description.into())
+    }
+
+    fn status(color: Color, emoji: &str, title: String, description: String) -> Self {
+        let mut embed = Self::new();
+        embed
+            .title(format!("{} {}", emoji, title))
+            .description(description)
+            .color(color)
+            .timestamp_now();
+        embed
+    }
+
+    /// The JSON object the embed is sent as.
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("an embed always serializes to JSON")
+    }
+
+    pub fn from_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(value)
+    }
+
+    /// Applies `patch` to the JSON object of the embed as a JSON merge patch (RFC 7396),
+    /// like `Message::merge_value`.
+    pub fn merge_value(&mut self, patch: serde_json::Value) -> Result<&mut Self, serde_json::Error> {
+        let mut value = self.to_value();
+        merge_patch(&mut value, patch);
+        *self = Self::from_value(value)?;
+        Ok(self)
+    }
+
+    pub fn title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.title = Some(title.into());
         self
     }
 
-    pub fn description(&mut self, description: &str) -> &mut Self {
-        self.description = Some(description.to_owned());
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.description = Some(description.into());
         self
     }
 
-    pub fn url(&mut self, url: &str) -> &mut Self {
-        self.url = Some(url.to_owned());
+    pub fn url(&mut self, url: impl Into<String>) -> &mut Self {
+        self.url = Some(url.into());
         self
     }
 
-    pub fn timestamp(&mut self, timestamp: &str) -> &mut Self {
-        self.timestamp = Some(timestamp.to_owned());
+    /// Example
+    /// ```no_run
+    /// # use std::time::SystemTime;
+    /// # use webhook::models::{Embed, Timestamp};
+    /// # fn main() -> Result<(), String> {
+    /// # let mut embed = Embed::new();
+    /// embed.timestamp(SystemTime::now());
+    /// // with the `chrono` feature: embed.timestamp(chrono::Utc::now());
+    /// embed.timestamp("2024-01-01T12:00:00Z".parse::<Timestamp>()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn timestamp<T: Into<Timestamp>>(&mut self, timestamp: T) -> &mut Self {
+        self.timestamp = Some(timestamp.into());
         self
     }
 
-    pub fn color(&mut self, color: &str) -> &mut Self {
-        self.color = Some(color.to_owned());
+    pub fn timestamp_now(&mut self) -> &mut Self {
+        self.timestamp(SystemTime::now())
+    }
+
+    /// Example
+    /// ```no_run
+    /// # use webhook::models::{Color, Embed};
+    /// # fn main() -> Result<(), String> {
+    /// # let mut embed = Embed::new();
+    /// embed.color(Color::BLURPLE);
+    /// embed.color(0xFF8800);
+    /// embed.color((255, 136, 0));
+    /// embed.color("#ff8800".parse::<Color>()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.color = Some(color.into());
         self
     }
 
-    pub fn footer(&mut self, text: &str, icon_url: Option<String>) -> &mut Self {
+    pub fn footer(
+        &mut self,
+        text: impl Into<String>,
+        icon_url: impl Into<Option<String>>,
+    ) -> &mut Self {
         self.footer = Some(EmbedFooter::new(text, icon_url));
         self
     }
 
-    pub fn image(&mut self, url: &str) -> &mut Self {
+    pub fn image(&mut self, url: impl Into<String>) -> &mut Self {
         self.image = Some(EmbedImage::new(url));
         self
     }
 
-    pub fn video(&mut self, url: &str) -> &mut Self {
+    pub fn video(&mut self, url: impl Into<String>) -> &mut Self {
         self.video = Some(EmbedVideo::new(url));
         self
     }
 
-    pub fn thumbnail(&mut self, url: &str) -> &mut Self {
+    pub fn thumbnail(&mut self, url: impl Into<String>) -> &mut Self {
         self.thumbnail = Some(EmbedThumbnail::new(url));
         self
     }
 
-    pub fn provider(&mut self, name: &str, url: &str) -> &mut Self {
+    pub fn provider(&mut self, name: impl Into<String>, url: impl Into<String>) -> &mut Self {
         self.provider = Some(EmbedProvider::new(name, url));
         self
     }
 
     pub fn author(
         &mut self,
-        name: &str,
-        url: Option<String>,
-        icon_url: Option<String>,
+        name: impl Into<String>,
+        url: impl Into<Option<String>>,
+        icon_url: impl Into<Option<String>>,
     ) -> &mut Self {
         self.author = Some(EmbedAuthor::new(name, url, icon_url));
         self
     }
 
-    pub fn field(&mut self, name: &str, value: &str, inline: bool) -> &mut Self {
-        if self.fields.len() == Embed::FIELDS_LEN_INTERVAL.max_allowed {
-            panic!("You can't have more than {} fields in an embed!", Embed::FIELDS_LEN_INTERVAL.max_allowed)
+    /// Shortens the texts that exceed Discord's limits, ending them with `…`.
+    pub fn truncate_to_limits(&mut self) -> &mut Self {
+        if let Some(title) = self.title.as_mut() {
+            truncate_in_place(title, Self::TITLE_LEN_INTERVAL.max_allowed);
+        }
+        if let Some(description) = self.description.as_mut() {
+            truncate_in_place(description, Self::DESCRIPTION_LEN_INTERVAL.max_allowed);
         }
+        if let Some(footer) = self.footer.as_mut() {
+            truncate_in_place(&mut footer.text, EmbedFooter::TEXT_LEN_INTERVAL.max_allowed);
+        }
+        if let Some(author) = self.author.as_mut() {
+            truncate_in_place(&mut author.name, EmbedAuthor::NAME_LEN_INTERVAL.max_allowed);
+        }
+        for field in self.fields.iter_mut() {
+            truncate_in_place(&mut field.name, EmbedField::NAME_LEN_INTERVAL.max_allowed);
+            truncate_in_place(&mut field.value, EmbedField::VALUE_LEN_INTERVAL.max_allowed);
+        }
+        self
+    }
+
+    /// The characters that count towards the total limit across all embeds of a message, see
+    /// `Message::remaining_embed_chars`.
+    pub fn char_count(&self) -> usize {
+        self.title.as_ref().map_or(0, |s| char_count(s))
+            + self.description.as_ref().map_or(0, |s| char_count(s))
+            + self.footer.as_ref().map_or(0, |f| char_count(&f.text))
+            + self.author.as_ref().map_or(0, |a| char_count(&a.name))
+            + self.fields.iter().map(|f| char_count(&f.name) + char_count(&f.value)).sum::<usize>()
+    }
+
+    /// How many more fields the embed can take.
+    pub fn field_slots_remaining(&self) -> usize {
+        Self::FIELDS_LEN_INTERVAL.max_allowed.saturating_sub(self.fields.len())
+    }
 
+    /// Adds a field. The field count limit is checked when the message is validated.
+    pub fn field(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+        inline: bool,
+    ) -> &mut Self {
         self.fields.push(EmbedField::new(name, value, inline));
         self
     }
 
     interval_member!(TITLE_LEN_INTERVAL, usize, 0, 256);
     interval_member!(DESCRIPTION_LEN_INTERVAL, usize, 0, 4096);
-    // enforced in field... by panic though... todo!
     interval_member!(FIELDS_LEN_INTERVAL, usize, 0, 25);
+    interval_member!(COLOR_INTERVAL, u32, 0, 0xFFFFFF);
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EmbedField {
     pub name: String,
     pub value: String,
+    #[serde(default)]
     pub inline: bool,
 }
 
 impl EmbedField {
-    pub fn new(name: &str, value: &str, inline: bool) -> Self {
+    pub fn new(name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
         Self {
-            name: name.to_owned(),
-            value: value.to_owned(),
+            name: name.into(),
+            value: value.into(),
             inline,
         }
     }
@@ -367,17 +1837,17 @@ impl EmbedField {
     interval_member!(VALUE_LEN_INTERVAL, usize, 0, 1024);
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EmbedFooter {
     pub text: String,
     pub icon_url: Option<String>,
 }
 
 impl EmbedFooter {
-    pub fn new(text: &str, icon_url: Option<String>) -> Self {
+    pub fn new(text: impl Into<String>, icon_url: impl Into<Option<String>>) -> Self {
         Self {
-            text: text.to_owned(),
-            icon_url,
+            text: text.into(),
+            icon_url: icon_url.into(),
         }
     }
     interval_member!(TEXT_LEN_INTERVAL, usize, 0, 2048);
@@ -387,35 +1857,35 @@ pub type EmbedImage = EmbedUrlSource;
 pub type EmbedThumbnail = EmbedUrlSource;
 pub type EmbedVideo = EmbedUrlSource;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EmbedUrlSource {
     pub url: String,
 }
 
 impl EmbedUrlSource {
-    pub fn new(url: &str) -> Self {
+    pub fn new(url: impl Into<String>) -> Self {
         Self {
-            url: url.to_owned(),
+            url: url.into(),
         }
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EmbedProvider {
     pub name: String,
     pub url: String,
 }
 
 impl EmbedProvider {
-    pub fn new(name: &str, url: &str) -> Self {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
         Self {
-            name: name.to_owned(),
-            url: url.to_owned(),
+            name: name.into(),
+            url: url.into(),
         }
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EmbedAuthor {
     pub name: String,
     pub url: Option<String>,
@@ -423,16 +1893,113 @@ pub struct EmbedAuthor {
 }
 
 impl EmbedAuthor {
-    pub fn new(name: &str, url: Option<String>, icon_url: Option<String>) -> Self {
+    pub fn new(
+        name: impl Into<String>,
+        url: impl Into<Option<String>>,
+        icon_url: impl Into<Option<String>>,
+    ) -> Self {
         Self {
-            name: name.to_owned(),
-            url,
-            icon_url,
+            name: name.into(),
+            url: url.into(),
+            icon_url: icon_url.into(),
         }
     }
     interval_member!(NAME_LEN_INTERVAL, usize, 0, 256);
 }
 
+/// A file of a message: one uploaded with it, or an existing one when editing a message.
+///
+/// Only the metadata is part of the JSON payload; the data of new files is uploaded next to it.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// The index of the upload for new files, the attachment id for existing ones.
+    pub id: Snowflake,
+    pub filename: String,
+    /// Alt text, e.g. for screen readers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The contents of a file to upload. `None` for existing attachments and for files
+    /// that are not downloaded from `source_url` yet.
+    #[serde(skip)]
+    pub data: Option<Vec<u8>>,
+    /// Where the client downloads the file from, see `Message::attachment_from_url`.
+    #[serde(skip)]
+    pub source_url: Option<String>,
+}
+
+impl Attachment {
+    pub fn new(id: Snowflake, filename: impl Into<String>, data: Option<Vec<u8>>) -> Self {
+        Self {
+            id,
+            filename: filename.into(),
+            description: None,
+            data,
+            source_url: None,
+        }
+    }
+
+    /// The size of the data to upload in bytes, 0 if the data is not known yet.
+    pub fn size(&self) -> u64 {
+        self.data.as_ref().map_or(0, |data| data.len() as u64)
+    }
+
+    /// Whether the file is uploaded with the message, rather than kept from an earlier one.
+    pub fn is_upload(&self) -> bool {
+        self.data.is_some() || self.source_url.is_some()
+    }
+
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Shrinks an image that exceeds the limits of `policy`, re-encoding it and changing the
+    /// extension of the filename to the new format. Anything that cannot be decoded as an
+    /// image is left as it is.
+    ///
+    /// Embeds have to refer to the image by its new name, e.g. `attachment://screenshot.jpg`.
+    #[cfg(feature = "image")]
+    pub fn fit_image(&mut self, policy: &ImagePolicy) -> &mut Self {
+        let fitted = match self.data.as_ref() {
+            Some(data) => policy.fit(data),
+            None => return self,
+        };
+        if let Ok(Some((data, extension))) = fitted {
+            let stem = match self.filename.rsplit_once('.') {
+                Some((stem, _)) => stem.to_owned(),
+                None => self.filename.clone(),
+            };
+            self.filename = format!("{}.{}", stem, extension);
+            self.data = Some(data);
+        }
+        self
+    }
+
+    /// Hides the file behind a spoiler by prefixing its name with `SPOILER_`.
+    pub fn spoiler(&mut self) -> &mut Self {
+        if !self.filename.starts_with(Self::SPOILER_PREFIX) {
+            self.filename.insert_str(0, Self::SPOILER_PREFIX);
+        }
+        self
+    }
+
+    const SPOILER_PREFIX: &'static str = "SPOILER_";
+    interval_member!(DESCRIPTION_LEN_INTERVAL, usize, 0, 1024);
+}
+
+impl fmt::Debug for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Attachment")
+            .field("id", &self.id)
+            .field("filename", &self.filename)
+            .field("description", &self.description)
+            .field("data", &self.data.as_ref().map(|data| format!("{} bytes", data.len())))
+            .field("source_url", &self.source_url)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AllowedMention {
     RoleMention,
     UserMention,
@@ -447,11 +2014,15 @@ fn resolve_allowed_mention_name(allowed_mention: AllowedMention) -> String {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct AllowedMentions {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub parse: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub roles: Option<Vec<Snowflake>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub users: Option<Vec<Snowflake>>,
+    #[serde(default)]
     pub replied_user: bool,
 }
 
@@ -463,9 +2034,8 @@ impl AllowedMentions {
         replied_user: bool,
     ) -> Self {
         let mut parse_strings: Vec<String> = vec![];
-        if parse.is_some() {
+        if let Some(parse) = parse {
             parse
-                .unwrap()
                 .into_iter()
                 .for_each(|x| parse_strings.push(resolve_allowed_mention_name(x)))
         }
@@ -477,13 +2047,72 @@ impl AllowedMentions {
             replied_user,
         }
     }
+
+    /// Allows no mention to ping.
+    pub fn none() -> Self {
+        Self::new(None, None, None, false)
+    }
+
+    /// Allows pinging exactly these users.
+    pub fn users<Ids>(&mut self, user_ids: Ids) -> &mut Self
+    where
+        Ids: IntoIterator,
+        Ids::Item: Into<Snowflake>,
+    {
+        self.users = Some(user_ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Allows pinging exactly these roles.
+    pub fn roles<Ids>(&mut self, role_ids: Ids) -> &mut Self
+    where
+        Ids: IntoIterator,
+        Ids::Item: Into<Snowflake>,
+    {
+        self.roles = Some(role_ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Allows pinging every user mentioned in the content.
+    pub fn all_users(&mut self) -> &mut Self {
+        self.allow_parsed(AllowedMention::UserMention)
+    }
+
+    /// Allows pinging every role mentioned in the content.
+    pub fn all_roles(&mut self) -> &mut Self {
+        self.allow_parsed(AllowedMention::RoleMention)
+    }
+
+    /// Allows `@everyone` and `@here` to ping.
+    pub fn everyone(&mut self) -> &mut Self {
+        self.allow_parsed(AllowedMention::EveryoneMention)
+    }
+
+    /// Whether the author of the replied-to message is pinged.
+    pub fn replied_user(&mut self, replied_user: bool) -> &mut Self {
+        self.replied_user = replied_user;
+        self
+    }
+
+    fn allow_parsed(&mut self, allowed_mention: AllowedMention) -> &mut Self {
+        let name = resolve_allowed_mention_name(allowed_mention);
+        let parse = self.parse.get_or_insert_with(Vec::new);
+        if !parse.contains(&name) {
+            parse.push(name);
+        }
+        self
+    }
+
+    interval_member!(ID_COUNT_INTERVAL, usize, 0, 100);
 }
 
 // ready to be extended with other components
 // non-composite here specifically means *not an action row*
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum NonCompositeComponent {
     Button(Button),
+    SelectMenu(SelectMenu),
+    EntitySelectMenu(EntitySelectMenu),
 }
 
 impl Serialize for NonCompositeComponent {
@@ -493,17 +2122,44 @@ impl Serialize for NonCompositeComponent {
     {
         match self {
             NonCompositeComponent::Button(button) => button.serialize(serializer),
+            NonCompositeComponent::SelectMenu(menu) => menu.serialize(serializer),
+            NonCompositeComponent::EntitySelectMenu(menu) => menu.serialize(serializer),
         }
     }
 }
 
-#[derive(Serialize, Debug)]
-pub struct ActionRow {
-    #[serde(rename = "type")]
-    pub component_type: u8,
+impl<'de> Deserialize<'de> for NonCompositeComponent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // the component type decides which struct the remaining fields belong to
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let component = match value.get("type").and_then(serde_json::Value::as_u64) {
+            Some(2) => serde_json::from_value(value).map(NonCompositeComponent::Button),
+            Some(3) => serde_json::from_value(value).map(NonCompositeComponent::SelectMenu),
+            Some(5..=8) => serde_json::from_value(value).map(NonCompositeComponent::EntitySelectMenu),
+            Some(other) => return Err(de::Error::custom(format!("unsupported component type {}", other))),
+            None => return Err(de::Error::missing_field("type")),
+        };
+        component.map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ActionRow {
+    #[serde(rename = "type")]
+    pub component_type: u8,
+    #[serde(default)]
     components: Vec<NonCompositeComponent>,
 }
 
+impl Default for ActionRow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ActionRow {
     fn new() -> ActionRow {
         ActionRow {
@@ -514,31 +2170,107 @@ impl ActionRow {
 
     pub fn link_button<Func>(&mut self, button_mutator: Func) -> &mut Self
     where
-        Func: Fn(&mut LinkButton) -> &mut LinkButton,
+        Func: FnOnce(&mut LinkButton) -> &mut LinkButton,
     {
         let mut button = LinkButton::new();
         button_mutator(&mut button);
         self.components.push(NonCompositeComponent::Button(
-            button.to_serializable_button(),
+            button.into_serializable_button(),
         ));
         self
     }
 
     pub fn regular_button<Func>(&mut self, button_mutator: Func) -> &mut Self
     where
-        Func: Fn(&mut RegularButton) -> &mut RegularButton,
+        Func: FnOnce(&mut RegularButton) -> &mut RegularButton,
     {
         let mut button = RegularButton::new();
         button_mutator(&mut button);
         self.components.push(NonCompositeComponent::Button(
-            button.to_serializable_button(),
+            button.into_serializable_button(),
         ));
         self
     }
+
+    /// Adds a string select menu. A select menu must be the only component of its action row.
+    ///
+    /// Example
+    /// ```no_run
+    /// # let mut message = webhook::models::Message::new();
+    /// # message.action_row(|row| {
+    /// row.select_menu(|menu| menu
+    ///     .custom_id("class")
+    ///     .placeholder("Choose a class")
+    ///     .option("Mage", "mage", |option| option.description("Turns enemies into sheep"))
+    ///     .option("Rogue", "rogue", |option| option.default(true)))
+    /// # });
+    /// ```
+    pub fn select_menu<Func>(&mut self, menu_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut SelectMenu) -> &mut SelectMenu,
+    {
+        let mut menu = SelectMenu::new();
+        menu_mutator(&mut menu);
+        self.components.push(NonCompositeComponent::SelectMenu(menu));
+        self
+    }
+
+    pub fn user_select<Func>(&mut self, menu_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut UserSelectMenu) -> &mut UserSelectMenu,
+    {
+        let mut menu = UserSelectMenu::new();
+        menu_mutator(&mut menu);
+        self.components.push(NonCompositeComponent::EntitySelectMenu(menu.into()));
+        self
+    }
+
+    pub fn role_select<Func>(&mut self, menu_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut RoleSelectMenu) -> &mut RoleSelectMenu,
+    {
+        let mut menu = RoleSelectMenu::new();
+        menu_mutator(&mut menu);
+        self.components.push(NonCompositeComponent::EntitySelectMenu(menu.into()));
+        self
+    }
+
+    pub fn mentionable_select<Func>(&mut self, menu_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut MentionableSelectMenu) -> &mut MentionableSelectMenu,
+    {
+        let mut menu = MentionableSelectMenu::new();
+        menu_mutator(&mut menu);
+        self.components.push(NonCompositeComponent::EntitySelectMenu(menu.into()));
+        self
+    }
+
+    /// Example
+    /// ```no_run
+    /// # use webhook::models::{ChannelType, Message};
+    /// # let mut message = Message::new();
+    /// # message.action_row(|row| {
+    /// row.channel_select(|menu| menu
+    ///     .custom_id("channel")
+    ///     .channel_type(ChannelType::GuildText)
+    ///     .max_values(3))
+    /// # });
+    /// ```
+    pub fn channel_select<Func>(&mut self, menu_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut ChannelSelectMenu) -> &mut ChannelSelectMenu,
+    {
+        let mut menu = ChannelSelectMenu::new();
+        menu_mutator(&mut menu);
+        self.components.push(NonCompositeComponent::EntitySelectMenu(menu.into()));
+        self
+    }
+
     interval_member!(BUTTON_COUNT_INTERVAL, usize, 0, 5);
+    interval_member!(SELECT_MENU_COUNT_INTERVAL, usize, 0, 1);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NonLinkButtonStyle {
     Primary,
     Secondary,
@@ -560,7 +2292,7 @@ impl NonLinkButtonStyle {
 // since link button has an explicit way of creation via the action row
 // this enum is kept hidden from the user ans the NonLinkButtonStyle is created to avoid
 // user confusion
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum ButtonStyles {
     Primary,
     Secondary,
@@ -585,7 +2317,23 @@ impl Serialize for ButtonStyles {
     }
 }
 
-#[derive(Serialize, Debug, Clone)]
+impl<'de> Deserialize<'de> for ButtonStyles {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(ButtonStyles::Primary),
+            2 => Ok(ButtonStyles::Secondary),
+            3 => Ok(ButtonStyles::Success),
+            4 => Ok(ButtonStyles::Danger),
+            5 => Ok(ButtonStyles::Link),
+            other => Err(de::Error::custom(format!("unknown button style {}", other))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PartialEmoji {
     pub id: Snowflake,
     pub name: String,
@@ -593,7 +2341,7 @@ pub struct PartialEmoji {
 }
 
 /// the button struct intended for serialized
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 struct Button {
     #[serde(rename = "type")]
     pub component_type: i8,
@@ -627,7 +2375,7 @@ impl Button {
 }
 
 /// Data holder for shared fields of link and regular buttons
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct ButtonCommonBase {
     pub label: Option<String>,
     pub emoji: Option<PartialEmoji>,
@@ -642,15 +2390,15 @@ impl ButtonCommonBase {
             disabled,
         }
     }
-    fn label(&mut self, label: &str) -> &mut Self {
-        self.label = Some(label.to_string());
+    fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label.into());
         self
     }
 
-    fn emoji(&mut self, emoji_id: Snowflake, name: &str, animated: bool) -> &mut Self {
+    fn emoji(&mut self, emoji_id: Snowflake, name: impl Into<String>, animated: bool) -> &mut Self {
         self.emoji = Some(PartialEmoji {
             id: emoji_id,
-            name: name.to_string(),
+            name: name.into(),
             animated: Some(animated),
         });
         self
@@ -666,8 +2414,13 @@ impl ButtonCommonBase {
 /// and generates setter functions that delegate their inputs to the `self.base`
 macro_rules! button_base_delegation {
     ($base:ident) => {
-        pub fn emoji(&mut self, emoji_id: &str, name: &str, animated: bool) -> &mut Self {
-            self.$base.emoji(emoji_id.to_string(), name, animated);
+        pub fn emoji<Id: Into<Snowflake>>(
+            &mut self,
+            emoji_id: Id,
+            name: impl Into<String>,
+            animated: bool,
+        ) -> &mut Self {
+            self.$base.emoji(emoji_id.into(), name, animated);
             self
         }
 
@@ -676,14 +2429,14 @@ macro_rules! button_base_delegation {
             self
         }
 
-        pub fn label(&mut self, label: &str) -> &mut Self {
+        pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
             self.$base.label(label);
             self
         }
     };
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LinkButton {
     button_base: ButtonCommonBase,
     url: Option<String>,
@@ -697,14 +2450,15 @@ impl LinkButton {
         }
     }
 
-    pub fn url(&mut self, url: &str) -> &mut Self {
-        self.url = Some(url.to_string());
+    pub fn url(&mut self, url: impl Into<String>) -> &mut Self {
+        self.url = Some(url.into());
         self
     }
 
     button_base_delegation!(button_base);
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RegularButton {
     button_base: ButtonCommonBase,
     custom_id: Option<String>,
@@ -720,8 +2474,8 @@ impl RegularButton {
         }
     }
 
-    pub fn custom_id(&mut self, custom_id: &str) -> &mut Self {
-        self.custom_id = Some(custom_id.to_string());
+    pub fn custom_id(&mut self, custom_id: impl Into<String>) -> &mut Self {
+        self.custom_id = Some(custom_id.into());
         self
     }
 
@@ -733,154 +2487,1199 @@ impl RegularButton {
     button_base_delegation!(button_base);
 }
 
-trait ToSerializableButton {
-    fn to_serializable_button(&self) -> Button;
+/// Data holder for shared fields of all select menu kinds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SelectMenuCommonBase {
+    custom_id: Option<String>,
+    placeholder: Option<String>,
+    min_values: Option<u8>,
+    max_values: Option<u8>,
+    disabled: Option<bool>,
+}
+
+impl SelectMenuCommonBase {
+    fn new() -> Self {
+        SelectMenuCommonBase {
+            custom_id: None,
+            placeholder: None,
+            min_values: None,
+            max_values: None,
+            disabled: None,
+        }
+    }
+
+    interval_member!(PLACEHOLDER_LEN_INTERVAL, usize, 0, 150);
+    interval_member!(MIN_VALUES_INTERVAL, u8, 0, 25);
+    interval_member!(MAX_VALUES_INTERVAL, u8, 1, 25);
+}
+
+/// a macro which takes an identifier (`base`) of the SelectMenuCommonBase (relative to `self`)
+/// and generates setter functions that write into the `self.base`
+macro_rules! select_menu_base_delegation {
+    ($base:ident) => {
+        pub fn custom_id(&mut self, custom_id: impl Into<String>) -> &mut Self {
+            self.$base.custom_id = Some(custom_id.into());
+            self
+        }
+
+        pub fn placeholder(&mut self, placeholder: impl Into<String>) -> &mut Self {
+            self.$base.placeholder = Some(placeholder.into());
+            self
+        }
+
+        /// Minimum number of items that must be chosen.
+        pub fn min_values(&mut self, min_values: u8) -> &mut Self {
+            self.$base.min_values = Some(min_values);
+            self
+        }
+
+        /// Maximum number of items that can be chosen.
+        pub fn max_values(&mut self, max_values: u8) -> &mut Self {
+            self.$base.max_values = Some(max_values);
+            self
+        }
+
+        pub fn disabled(&mut self, disabled: bool) -> &mut Self {
+            self.$base.disabled = Some(disabled);
+            self
+        }
+    };
+}
+
+/// A string select menu, letting users choose from a list of options.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SelectMenu {
+    #[serde(rename = "type")]
+    component_type: u8,
+    #[serde(flatten)]
+    select_menu_base: SelectMenuCommonBase,
+    #[serde(default)]
+    options: Vec<SelectMenuOption>,
+}
+
+impl SelectMenu {
+    fn new() -> Self {
+        SelectMenu {
+            component_type: 3,
+            select_menu_base: SelectMenuCommonBase::new(),
+            options: vec![],
+        }
+    }
+
+    pub fn option<Func>(
+        &mut self,
+        label: impl Into<String>,
+        value: impl Into<String>,
+        option_mutator: Func,
+    ) -> &mut Self
+    where
+        Func: FnOnce(&mut SelectMenuOption) -> &mut SelectMenuOption,
+    {
+        let mut option = SelectMenuOption::new(label, value);
+        option_mutator(&mut option);
+        self.options.push(option);
+        self
+    }
+
+    select_menu_base_delegation!(select_menu_base);
+
+    interval_member!(OPTION_COUNT_INTERVAL, usize, 1, 25);
+}
+
+/// Channel types a `ChannelSelectMenu` can be restricted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelType {
+    GuildText,
+    Dm,
+    GuildVoice,
+    GroupDm,
+    GuildCategory,
+    GuildAnnouncement,
+    AnnouncementThread,
+    PublicThread,
+    PrivateThread,
+    GuildStageVoice,
+    GuildDirectory,
+    GuildForum,
+    GuildMedia,
+}
+
+impl Serialize for ChannelType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let to_serialize = match *self {
+            ChannelType::GuildText => 0,
+            ChannelType::Dm => 1,
+            ChannelType::GuildVoice => 2,
+            ChannelType::GroupDm => 3,
+            ChannelType::GuildCategory => 4,
+            ChannelType::GuildAnnouncement => 5,
+            ChannelType::AnnouncementThread => 10,
+            ChannelType::PublicThread => 11,
+            ChannelType::PrivateThread => 12,
+            ChannelType::GuildStageVoice => 13,
+            ChannelType::GuildDirectory => 14,
+            ChannelType::GuildForum => 15,
+            ChannelType::GuildMedia => 16,
+        };
+        serializer.serialize_u8(to_serialize)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChannelType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(ChannelType::GuildText),
+            1 => Ok(ChannelType::Dm),
+            2 => Ok(ChannelType::GuildVoice),
+            3 => Ok(ChannelType::GroupDm),
+            4 => Ok(ChannelType::GuildCategory),
+            5 => Ok(ChannelType::GuildAnnouncement),
+            10 => Ok(ChannelType::AnnouncementThread),
+            11 => Ok(ChannelType::PublicThread),
+            12 => Ok(ChannelType::PrivateThread),
+            13 => Ok(ChannelType::GuildStageVoice),
+            14 => Ok(ChannelType::GuildDirectory),
+            15 => Ok(ChannelType::GuildForum),
+            16 => Ok(ChannelType::GuildMedia),
+            other => Err(de::Error::custom(format!("unknown channel type {}", other))),
+        }
+    }
+}
+
+/// A select menu whose options are populated by Discord (users, roles, channels, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserSelectMenu {
+    select_menu_base: SelectMenuCommonBase,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleSelectMenu {
+    select_menu_base: SelectMenuCommonBase,
+}
+
+/// A select menu offering both users and roles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionableSelectMenu {
+    select_menu_base: SelectMenuCommonBase,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelSelectMenu {
+    select_menu_base: SelectMenuCommonBase,
+    channel_types: Vec<ChannelType>,
+}
+
+impl UserSelectMenu {
+    fn new() -> Self {
+        UserSelectMenu {
+            select_menu_base: SelectMenuCommonBase::new(),
+        }
+    }
+
+    select_menu_base_delegation!(select_menu_base);
+}
+
+impl RoleSelectMenu {
+    fn new() -> Self {
+        RoleSelectMenu {
+            select_menu_base: SelectMenuCommonBase::new(),
+        }
+    }
+
+    select_menu_base_delegation!(select_menu_base);
+}
+
+impl MentionableSelectMenu {
+    fn new() -> Self {
+        MentionableSelectMenu {
+            select_menu_base: SelectMenuCommonBase::new(),
+        }
+    }
+
+    select_menu_base_delegation!(select_menu_base);
+}
+
+impl ChannelSelectMenu {
+    fn new() -> Self {
+        ChannelSelectMenu {
+            select_menu_base: SelectMenuCommonBase::new(),
+            channel_types: vec![],
+        }
+    }
+
+    /// Restricts the offered channels to the given type. May be called repeatedly.
+    pub fn channel_type(&mut self, channel_type: ChannelType) -> &mut Self {
+        self.channel_types.push(channel_type);
+        self
+    }
+
+    select_menu_base_delegation!(select_menu_base);
+}
+
+/// the auto-populated select menu struct intended for serialization
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct EntitySelectMenu {
+    #[serde(rename = "type")]
+    component_type: u8,
+    #[serde(flatten)]
+    select_menu_base: SelectMenuCommonBase,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    channel_types: Vec<ChannelType>,
+}
+
+impl EntitySelectMenu {
+    fn new(component_type: u8, select_menu_base: SelectMenuCommonBase, channel_types: Vec<ChannelType>) -> Self {
+        EntitySelectMenu {
+            component_type,
+            select_menu_base,
+            channel_types,
+        }
+    }
+}
+
+impl From<UserSelectMenu> for EntitySelectMenu {
+    fn from(menu: UserSelectMenu) -> Self {
+        EntitySelectMenu::new(5, menu.select_menu_base, vec![])
+    }
+}
+
+impl From<RoleSelectMenu> for EntitySelectMenu {
+    fn from(menu: RoleSelectMenu) -> Self {
+        EntitySelectMenu::new(6, menu.select_menu_base, vec![])
+    }
+}
+
+impl From<MentionableSelectMenu> for EntitySelectMenu {
+    fn from(menu: MentionableSelectMenu) -> Self {
+        EntitySelectMenu::new(7, menu.select_menu_base, vec![])
+    }
+}
+
+impl From<ChannelSelectMenu> for EntitySelectMenu {
+    fn from(menu: ChannelSelectMenu) -> Self {
+        EntitySelectMenu::new(8, menu.select_menu_base, menu.channel_types)
+    }
+}
+
+/// A choice of a `SelectMenu`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SelectMenuOption {
+    label: String,
+    value: String,
+    description: Option<String>,
+    emoji: Option<PartialEmoji>,
+    default: Option<bool>,
 }
 
-impl ToSerializableButton for LinkButton {
-    fn to_serializable_button(&self) -> Button {
+impl SelectMenuOption {
+    fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
+        SelectMenuOption {
+            label: label.into(),
+            value: value.into(),
+            description: None,
+            emoji: None,
+            default: None,
+        }
+    }
+
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn emoji<Id: Into<Snowflake>>(
+        &mut self,
+        emoji_id: Id,
+        name: impl Into<String>,
+        animated: bool,
+    ) -> &mut Self {
+        self.emoji = Some(PartialEmoji {
+            id: emoji_id.into(),
+            name: name.into(),
+            animated: Some(animated),
+        });
+        self
+    }
+
+    /// Whether the option is selected by default.
+    pub fn default(&mut self, default: bool) -> &mut Self {
+        self.default = Some(default);
+        self
+    }
+
+    interval_member!(LABEL_LEN_INTERVAL, usize, 1, 100);
+    interval_member!(VALUE_LEN_INTERVAL, usize, 1, 100);
+    interval_member!(DESCRIPTION_LEN_INTERVAL, usize, 0, 100);
+}
+
+trait IntoSerializableButton {
+    fn into_serializable_button(self) -> Button;
+}
+
+impl IntoSerializableButton for LinkButton {
+    fn into_serializable_button(self) -> Button {
         Button::new(
             Some(ButtonStyles::Link),
-            self.button_base.label.clone(),
-            self.button_base.emoji.clone(),
-            self.url.clone(),
+            self.button_base.label,
+            self.button_base.emoji,
+            self.url,
             None,
             self.button_base.disabled,
         )
     }
 }
 
-impl ToSerializableButton for RegularButton {
-    fn to_serializable_button(&self) -> Button {
+impl IntoSerializableButton for RegularButton {
+    fn into_serializable_button(self) -> Button {
         Button::new(
-            self.style.clone().map(|s| s.get_button_style()),
-            self.button_base.label.clone(),
-            self.button_base.emoji.clone(),
+            self.style.map(|s| s.get_button_style()),
+            self.button_base.label,
+            self.button_base.emoji,
             None,
-            self.custom_id.clone(),
+            self.custom_id,
             self.button_base.disabled,
         )
     }
 }
 
+/// A top-level component of a components v2 message (see `Message::IS_COMPONENTS_V2`).
+///
+/// Containers hold the same components, except other containers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutComponent {
+    ActionRow(ActionRow),
+    Section(Section),
+    TextDisplay(TextDisplay),
+    MediaGallery(MediaGallery),
+    Separator(Separator),
+    Container(Container),
+}
+
+impl LayoutComponent {
+    /// The number of components Discord counts for this one, including nested components.
+    fn component_count(&self) -> usize {
+        match self {
+            LayoutComponent::ActionRow(row) => 1 + row.components.len(),
+            LayoutComponent::Section(section) => {
+                1 + section.components.len() + section.accessory.iter().count()
+            }
+            LayoutComponent::Container(container) => {
+                1 + container
+                    .components
+                    .iter()
+                    .map(LayoutComponent::component_count)
+                    .sum::<usize>()
+            }
+            LayoutComponent::TextDisplay(_)
+            | LayoutComponent::MediaGallery(_)
+            | LayoutComponent::Separator(_) => 1,
+        }
+    }
+}
+
+impl Serialize for LayoutComponent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            LayoutComponent::ActionRow(row) => row.serialize(serializer),
+            LayoutComponent::Section(section) => section.serialize(serializer),
+            LayoutComponent::TextDisplay(text) => text.serialize(serializer),
+            LayoutComponent::MediaGallery(gallery) => gallery.serialize(serializer),
+            LayoutComponent::Separator(separator) => separator.serialize(serializer),
+            LayoutComponent::Container(container) => container.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LayoutComponent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let component = match value.get("type").and_then(serde_json::Value::as_u64) {
+            Some(1) => serde_json::from_value(value).map(LayoutComponent::ActionRow),
+            Some(9) => serde_json::from_value(value).map(LayoutComponent::Section),
+            Some(10) => serde_json::from_value(value).map(LayoutComponent::TextDisplay),
+            Some(12) => serde_json::from_value(value).map(LayoutComponent::MediaGallery),
+            Some(14) => serde_json::from_value(value).map(LayoutComponent::Separator),
+            Some(17) => serde_json::from_value(value).map(LayoutComponent::Container),
+            Some(other) => return Err(de::Error::custom(format!("unsupported component type {}", other))),
+            None => return Err(de::Error::missing_field("type")),
+        };
+        component.map_err(de::Error::custom)
+    }
+}
+
+/// A block of markdown text in a components v2 message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TextDisplay {
+    #[serde(rename = "type")]
+    component_type: u8,
+    pub content: String,
+}
+
+impl TextDisplay {
+    fn new(content: impl Into<String>) -> Self {
+        TextDisplay {
+            component_type: 10,
+            content: content.into(),
+        }
+    }
+}
+
+/// Text next to an accessory, a thumbnail or a button.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    #[serde(rename = "type")]
+    component_type: u8,
+    components: Vec<TextDisplay>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accessory: Option<SectionAccessory>,
+}
+
+impl Default for Section {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Section {
+    fn new() -> Self {
+        Section {
+            component_type: 9,
+            components: vec![],
+            accessory: None,
+        }
+    }
+
+    pub fn text(&mut self, content: impl Into<String>) -> &mut Self {
+        self.components.push(TextDisplay::new(content));
+        self
+    }
+
+    pub fn thumbnail<Func>(&mut self, url: impl Into<String>, thumbnail_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut Thumbnail) -> &mut Thumbnail,
+    {
+        let mut thumbnail = Thumbnail::new(url);
+        thumbnail_mutator(&mut thumbnail);
+        self.accessory = Some(SectionAccessory::Thumbnail(thumbnail));
+        self
+    }
+
+    pub fn link_button<Func>(&mut self, button_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut LinkButton) -> &mut LinkButton,
+    {
+        let mut button = LinkButton::new();
+        button_mutator(&mut button);
+        self.accessory = Some(SectionAccessory::Button(button.into_serializable_button()));
+        self
+    }
+
+    pub fn regular_button<Func>(&mut self, button_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut RegularButton) -> &mut RegularButton,
+    {
+        let mut button = RegularButton::new();
+        button_mutator(&mut button);
+        self.accessory = Some(SectionAccessory::Button(button.into_serializable_button()));
+        self
+    }
+
+    interval_member!(TEXT_DISPLAY_COUNT_INTERVAL, usize, 1, 3);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SectionAccessory {
+    Thumbnail(Thumbnail),
+    Button(Button),
+}
+
+impl Serialize for SectionAccessory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            SectionAccessory::Thumbnail(thumbnail) => thumbnail.serialize(serializer),
+            SectionAccessory::Button(button) => button.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SectionAccessory {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let accessory = match value.get("type").and_then(serde_json::Value::as_u64) {
+            Some(2) => serde_json::from_value(value).map(SectionAccessory::Button),
+            Some(11) => serde_json::from_value(value).map(SectionAccessory::Thumbnail),
+            Some(other) => return Err(de::Error::custom(format!("unsupported accessory type {}", other))),
+            None => return Err(de::Error::missing_field("type")),
+        };
+        accessory.map_err(de::Error::custom)
+    }
+}
+
+/// A reference to an image or video, by URL or as `attachment://<filename>`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnfurledMediaItem {
+    pub url: String,
+}
+
+/// A small image shown next to the text of a `Section`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    #[serde(rename = "type")]
+    component_type: u8,
+    media: UnfurledMediaItem,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spoiler: Option<bool>,
+}
+
+impl Thumbnail {
+    fn new(url: impl Into<String>) -> Self {
+        Thumbnail {
+            component_type: 11,
+            media: UnfurledMediaItem {
+                url: url.into(),
+            },
+            description: None,
+            spoiler: None,
+        }
+    }
+
+    /// Alt text of the image.
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn spoiler(&mut self, spoiler: bool) -> &mut Self {
+        self.spoiler = Some(spoiler);
+        self
+    }
+}
+
+/// A grid of up to ten images or videos.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MediaGallery {
+    #[serde(rename = "type")]
+    component_type: u8,
+    items: Vec<MediaGalleryItem>,
+}
+
+impl Default for MediaGallery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediaGallery {
+    fn new() -> Self {
+        MediaGallery {
+            component_type: 12,
+            items: vec![],
+        }
+    }
+
+    pub fn item<Func>(&mut self, url: impl Into<String>, item_mutator: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut MediaGalleryItem) -> &mut MediaGalleryItem,
+    {
+        let mut item = MediaGalleryItem::new(url);
+        item_mutator(&mut item);
+        self.items.push(item);
+        self
+    }
+
+    interval_member!(ITEM_COUNT_INTERVAL, usize, 1, 10);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MediaGalleryItem {
+    media: UnfurledMediaItem,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spoiler: Option<bool>,
+}
+
+impl MediaGalleryItem {
+    fn new(url: impl Into<String>) -> Self {
+        MediaGalleryItem {
+            media: UnfurledMediaItem {
+                url: url.into(),
+            },
+            description: None,
+            spoiler: None,
+        }
+    }
+
+    /// Alt text of the image or video.
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn spoiler(&mut self, spoiler: bool) -> &mut Self {
+        self.spoiler = Some(spoiler);
+        self
+    }
+}
+
+/// Vertical space between components, optionally with a dividing line.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Separator {
+    #[serde(rename = "type")]
+    component_type: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    divider: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spacing: Option<SeparatorSpacing>,
+}
+
+impl Default for Separator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Separator {
+    fn new() -> Self {
+        Separator {
+            component_type: 14,
+            divider: None,
+            spacing: None,
+        }
+    }
+
+    /// Whether a line is drawn. Discord draws one by default.
+    pub fn divider(&mut self, divider: bool) -> &mut Self {
+        self.divider = Some(divider);
+        self
+    }
+
+    pub fn spacing(&mut self, spacing: SeparatorSpacing) -> &mut Self {
+        self.spacing = Some(spacing);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeparatorSpacing {
+    Small,
+    Large,
+}
+
+impl Serialize for SeparatorSpacing {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let to_serialize = match *self {
+            SeparatorSpacing::Small => 1,
+            SeparatorSpacing::Large => 2,
+        };
+        serializer.serialize_u8(to_serialize)
+    }
+}
+
+impl<'de> Deserialize<'de> for SeparatorSpacing {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(SeparatorSpacing::Small),
+            2 => Ok(SeparatorSpacing::Large),
+            other => Err(de::Error::custom(format!("unknown separator spacing {}", other))),
+        }
+    }
+}
+
+/// A box around other components, like an embed, with an optional accent color.
+///
+/// Example
+/// ```no_run
+/// # use webhook::models::{Color, Message};
+/// # const RUNBOOK: &str = "https://wiki.example.com/runbooks/payments";
+/// # let mut message = Message::new();
+/// message.container(|container| container
+///     .accent_color(Color::RED)
+///     .text_display("## Payment provider unreachable")
+///     .separator(|separator| separator)
+///     .action_row(|row| row.link_button(|button| button.label("Runbook").url(RUNBOOK))));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Container {
+    #[serde(rename = "type")]
+    component_type: u8,
+    components: Vec<LayoutComponent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accent_color: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spoiler: Option<bool>,
+}
+
+impl Default for Container {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Container {
+    fn new() -> Self {
+        Container {
+            component_type: 17,
+            components: vec![],
+            accent_color: None,
+            spoiler: None,
+        }
+    }
+
+    pub fn accent_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.accent_color = Some(color.into());
+        self
+    }
+
+    pub fn spoiler(&mut self, spoiler: bool) -> &mut Self {
+        self.spoiler = Some(spoiler);
+        self
+    }
+
+    pub fn action_row<Func>(&mut self, func: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut ActionRow) -> &mut ActionRow,
+    {
+        let mut row = ActionRow::new();
+        func(&mut row);
+        self.components.push(LayoutComponent::ActionRow(row));
+        self
+    }
+
+    layout_builders!();
+
+    fn push_layout(&mut self, component: LayoutComponent) {
+        self.components.push(component);
+    }
+}
+
 /// A trait for checking that an API message component is compatible with the official Discord API constraints
 ///
 /// This trait should be implemented for any components for which the Discord API documentation states
 /// limitations (maximum count, maximum length, uniqueness with respect to other components, restrictions
 /// on children components, ...)
+///
+/// Violations are recorded in the context instead of being returned, so that a single pass
+/// finds all of them.
 pub(crate) trait DiscordApiCompatible {
-    fn check_compatibility(&self, context: &mut MessageContext) -> Result<(), String>;
+    fn check_compatibility(&self, context: &mut MessageContext);
 }
 
 impl DiscordApiCompatible for NonCompositeComponent {
-    fn check_compatibility(&self, context: &mut MessageContext) -> Result<(), String> {
+    fn check_compatibility(&self, context: &mut MessageContext) {
         match self {
             NonCompositeComponent::Button(b) => b.check_compatibility(context),
+            NonCompositeComponent::SelectMenu(m) => m.check_compatibility(context),
+            NonCompositeComponent::EntitySelectMenu(m) => m.check_compatibility(context),
         }
     }
 }
 
 impl DiscordApiCompatible for Button {
-    fn check_compatibility(&self, context: &mut MessageContext) -> Result<(), String> {
+    fn check_compatibility(&self, context: &mut MessageContext) {
         if let Some(label) = &self.label {
-            interval_check(&Message::LABEL_LEN_INTERVAL, &label.len(), "Label length")?;
+            context.check(interval_check(&Message::LABEL_LEN_INTERVAL, &char_count(label), "Label length"));
         }
 
-        return match self.style {
-            None => Err("Button style must be set!".to_string()),
-            Some(ButtonStyles::Link) => {
-                if self.url.is_none() {
-                    Err("Url of a Link button must be set!".to_string())
-                } else {
-                    Ok(())
-                }
-            }
+        match self.style {
+            None => context.report("Button style must be set!".to_string()),
+            Some(ButtonStyles::Link) => match self.url.as_ref() {
+                Some(url) => context.check(url_check(url, &BUTTON_SCHEMES, "Link button URL")),
+                None => context.report("Url of a Link button must be set!".to_string()),
+            },
             // list all remaining in case a style with different requirements is added
             Some(ButtonStyles::Danger)
             | Some(ButtonStyles::Primary)
             | Some(ButtonStyles::Success)
             | Some(ButtonStyles::Secondary) => {
-                return if let Some(id) = self.custom_id.as_ref() {
-                    context.register_button(id)
+                if let Some(id) = self.custom_id.as_ref() {
+                    context.register_button(id);
                 } else {
-                    Err("Custom ID of a NonLink button must be set!".to_string())
-                };
+                    context.report("Custom ID of a NonLink button must be set!".to_string());
+                }
             }
-        };
+        }
     }
 }
 
 impl DiscordApiCompatible for ActionRow {
-    fn check_compatibility(&self, context: &mut MessageContext) -> Result<(), String> {
-        context.register_action_row();
+    fn check_compatibility(&self, context: &mut MessageContext) {
         if self.components.is_empty() {
-            return Err("Empty action row detected!".to_string());
+            context.report("Empty action row detected!".to_string());
         }
 
-        self.components.iter().fold(Ok(()), |acc, component| {
-            acc.and(component.check_compatibility(context))
-        })
+        context.within_action_row(|context| {
+            for (index, component) in self.components.iter().enumerate() {
+                context.within(format!("components[{}]", index), |context| {
+                    component.check_compatibility(context)
+                });
+            }
+        });
     }
 }
 
 impl DiscordApiCompatible for Message {
-    fn check_compatibility(&self, context: &mut MessageContext) -> Result<(), String> {
-        interval_check(
-            &Message::ACTION_ROW_COUNT_INTERVAL,
-            &self.action_rows.len(),
-            "Action row count")?;
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        if let Some(content) = self.content.as_ref() {
+            context.check(interval_check(
+                &Message::CONTENT_LEN_INTERVAL,
+                &char_count(content),
+                "Content length"));
+        }
+        if let Some(username) = self.username.as_ref() {
+            context.within("username".to_string(), |context| check_webhook_name(username, "Username", context));
+        }
+        context.check(interval_check(
+            &Message::EMBED_COUNT_INTERVAL,
+            &self.embeds.len(),
+            "Embed count"));
+        if self.flags & Message::IS_COMPONENTS_V2 != 0 {
+            if self.content.is_some() || !self.embeds.is_empty() {
+                context.report("A components v2 message cannot have content or embeds!".to_string());
+            }
+            let component_count = self.action_rows.iter().map(|row| 1 + row.components.len()).sum::<usize>()
+                + self.layout.iter().map(LayoutComponent::component_count).sum::<usize>();
+            context.check(interval_check(
+                &Message::COMPONENTS_V2_COUNT_INTERVAL,
+                &component_count,
+                "Component count"));
+        } else {
+            context.check(interval_check(
+                &Message::ACTION_ROW_COUNT_INTERVAL,
+                &self.action_rows.len(),
+                "Action row count"));
+            if !self.layout.is_empty() {
+                context.report("Layout components require the IS_COMPONENTS_V2 flag!".to_string());
+            }
+        }
+
+        for key in self.extra.keys() {
+            if Message::MODELED_FIELDS.contains(&key.as_str()) {
+                context.report(format!("Extra field {} is modeled by Message and must be set through it", key));
+            }
+        }
+
+        if let Some(allowed_mentions) = self.allow_mentions.as_ref() {
+            context.within("allowed_mentions".to_string(), |context| {
+                allowed_mentions.check_compatibility(context)
+            });
+        }
+
+        for (index, embed) in self.embeds.iter().enumerate() {
+            context.within(format!("embeds[{}]", index), |context| {
+                embed.check_compatibility(context)
+            });
+        }
+
+        for (index, row) in self.action_rows.iter().enumerate() {
+            context.within(format!("components[{}]", index), |context| {
+                row.check_compatibility(context)
+            });
+        }
+        context.check(interval_check(
+            &Message::ATTACHMENT_COUNT_INTERVAL,
+            &self.attachments.len(),
+            "Attachment count"));
+        let mut attachment_ids = HashSet::new();
+        for (index, attachment) in self.attachments.iter().enumerate() {
+            context.within(format!("attachments[{}]", index), |context| {
+                if !attachment_ids.insert(attachment.id) {
+                    context.report(format!("Attempt to use the same attachment ID ({}) twice!", attachment.id));
+                }
+                attachment.check_compatibility(context);
+                if attachment.size() > self.upload_limit {
+                    context.report(format!(
+                        "Attachment size ({} bytes) exceeds the upload limit of {} bytes!",
+                        attachment.size(),
+                        self.upload_limit
+                    ));
+                }
+            });
+        }
+        let upload_size: u64 = self.attachments.iter().map(Attachment::size).sum();
+        let file_too_large = self.attachments.iter().any(|attachment| attachment.size() > self.upload_limit);
+        if upload_size > self.upload_limit && !file_too_large {
+            context.report(format!(
+                "Total attachment size ({} bytes) exceeds the upload limit of {} bytes!",
+                upload_size, self.upload_limit
+            ));
+        }
 
-        self.embeds
-            .iter()
-            .fold(Ok(()), |acc, emb| acc.and(emb.check_compatibility(context)))?;
+        // the layout is sent after the action rows
+        for (index, component) in self.layout.iter().enumerate() {
+            context.within(format!("components[{}]", self.action_rows.len() + index), |context| {
+                component.check_compatibility(context)
+            });
+        }
+    }
+}
 
-        self.action_rows
-            .iter()
-            .fold(Ok(()), |acc, row| acc.and(row.check_compatibility(context)))
+impl DiscordApiCompatible for Attachment {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        if self.filename.is_empty() {
+            context.report("Attachment filename must be set!".to_string());
+        }
+        if let Some(description) = self.description.as_ref() {
+            context.check(interval_check(&Self::DESCRIPTION_LEN_INTERVAL, &char_count(description), "Attachment description length"));
+        }
+    }
+}
+
+impl DiscordApiCompatible for LayoutComponent {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        match self {
+            LayoutComponent::ActionRow(row) => row.check_compatibility(context),
+            LayoutComponent::Section(section) => section.check_compatibility(context),
+            LayoutComponent::TextDisplay(text) => context.register_text_display(&text.content),
+            LayoutComponent::MediaGallery(gallery) => gallery.check_compatibility(context),
+            LayoutComponent::Separator(_) => {}
+            LayoutComponent::Container(container) => container.check_compatibility(context),
+        }
+    }
+}
+
+impl DiscordApiCompatible for Section {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        context.check(interval_check(&Self::TEXT_DISPLAY_COUNT_INTERVAL, &self.components.len(), "Section text display count"));
+        for text in self.components.iter() {
+            context.register_text_display(&text.content);
+        }
+
+        match self.accessory.as_ref() {
+            None => context.report("Accessory of a section must be set!".to_string()),
+            Some(SectionAccessory::Thumbnail(thumbnail)) => {
+                context.check(url_check(&thumbnail.media.url, &MEDIA_SCHEMES, "Thumbnail URL"));
+                if let Some(description) = thumbnail.description.as_ref() {
+                    context.check(interval_check(&Message::MEDIA_DESCRIPTION_LEN_INTERVAL, &char_count(description), "Thumbnail description length"));
+                }
+            }
+            Some(SectionAccessory::Button(button)) => {
+                // the accessory button does not share a row with other components
+                context.within_action_row(|context| {
+                    context.within("accessory".to_string(), |context| button.check_compatibility(context))
+                });
+            }
+        }
+    }
+}
+
+impl DiscordApiCompatible for MediaGallery {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        context.check(interval_check(&Self::ITEM_COUNT_INTERVAL, &self.items.len(), "Media gallery item count"));
+        for (index, item) in self.items.iter().enumerate() {
+            context.within(format!("items[{}]", index), |context| {
+                context.check(url_check(&item.media.url, &MEDIA_SCHEMES, "Media URL"));
+                if let Some(description) = item.description.as_ref() {
+                    context.check(interval_check(&Message::MEDIA_DESCRIPTION_LEN_INTERVAL, &char_count(description), "Media description length"))
+                }
+            });
+        }
+    }
+}
+
+impl DiscordApiCompatible for Container {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        if self.components.is_empty() {
+            context.report("Empty container detected!".to_string());
+        }
+        if let Some(color) = self.accent_color {
+            context.check(interval_check(&Embed::COLOR_INTERVAL, &color.value(), "Container accent color"));
+        }
+
+        for (index, component) in self.components.iter().enumerate() {
+            context.within(format!("components[{}]", index), |context| {
+                if let LayoutComponent::Container(_) = component {
+                    context.report("A container cannot contain another container!".to_string());
+                }
+                component.check_compatibility(context)
+            });
+        }
+    }
+}
+
+impl DiscordApiCompatible for AllowedMentions {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        let parsed = |name: &str| {
+            self.parse
+                .as_ref()
+                .is_some_and(|parse| parse.iter().any(|p| p == name))
+        };
+
+        if let Some(users) = self.users.as_ref() {
+            if parsed("users") {
+                context.report("Allowed mentions cannot parse all users and list explicit users at the same time!".to_string());
+            }
+            context.check(interval_check(&Self::ID_COUNT_INTERVAL, &users.len(), "Allowed mentions user count"));
+        }
+        if let Some(roles) = self.roles.as_ref() {
+            if parsed("roles") {
+                context.report("Allowed mentions cannot parse all roles and list explicit roles at the same time!".to_string());
+            }
+            context.check(interval_check(&Self::ID_COUNT_INTERVAL, &roles.len(), "Allowed mentions role count"));
+        }
     }
 }
 
 impl DiscordApiCompatible for Embed {
-    fn check_compatibility(&self, context: &mut MessageContext) -> Result<(), String> {
-        context.register_embed(self)?;
-        interval_check(&Self::FIELDS_LEN_INTERVAL, &self.fields.len(), "Embed field count")?;
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        context.register_embed(self);
+        context.check(interval_check(&Self::FIELDS_LEN_INTERVAL, &self.fields.len(), "Embed field count"));
+
+        if let Some(color) = self.color {
+            context.check(interval_check(&Self::COLOR_INTERVAL, &color.value(), "Embed color"));
+        }
 
         if let Some(title) = self.title.as_ref() {
-            interval_check(&Self::TITLE_LEN_INTERVAL, &title.len(), "Embed title length")?;
+            context.check(interval_check(&Self::TITLE_LEN_INTERVAL, &char_count(title), "Embed title length"));
         }
 
         if let Some(description) = self.description.as_ref() {
-            interval_check(&Self::DESCRIPTION_LEN_INTERVAL, &description.len(), "Embed description length")?;
+            context.check(interval_check(&Self::DESCRIPTION_LEN_INTERVAL, &char_count(description), "Embed description length"));
         }
 
-        self.author.as_ref().map_or_else(|| Ok(()), |a| a.check_compatibility(context))?;
-        self.footer.as_ref().map_or_else(|| Ok(()), |f| f.check_compatibility(context))?;
+        if let Some(url) = self.url.as_ref() {
+            context.check(url_check(url, &LINK_SCHEMES, "Embed URL"));
+        }
+        if let Some(image) = self.image.as_ref() {
+            context.check(url_check(&image.url, &MEDIA_SCHEMES, "Embed image URL"));
+        }
+        if let Some(thumbnail) = self.thumbnail.as_ref() {
+            context.check(url_check(&thumbnail.url, &MEDIA_SCHEMES, "Embed thumbnail URL"));
+        }
+        if let Some(video) = self.video.as_ref() {
+            context.check(url_check(&video.url, &MEDIA_SCHEMES, "Embed video URL"));
+        }
 
-        for field in self.fields.iter() {
-            field.check_compatibility(context)?;
+        if let Some(author) = self.author.as_ref() {
+            context.within("author".to_string(), |context| author.check_compatibility(context));
+        }
+        if let Some(footer) = self.footer.as_ref() {
+            context.within("footer".to_string(), |context| footer.check_compatibility(context));
+        }
+
+        for (index, field) in self.fields.iter().enumerate() {
+            context.within(format!("fields[{}]", index), |context| {
+                field.check_compatibility(context)
+            });
         }
-        Ok(())
     }
 }
 
 impl DiscordApiCompatible for EmbedAuthor {
-    fn check_compatibility(&self, _context: &mut MessageContext) -> Result<(), String> {
-        interval_check(&Self::NAME_LEN_INTERVAL, &self.name.len(), "Embed author name length")?;
-        Ok(())
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        context.check(interval_check(&Self::NAME_LEN_INTERVAL, &char_count(&self.name), "Embed author name length"));
+        if let Some(url) = self.url.as_ref() {
+            context.check(url_check(url, &LINK_SCHEMES, "Embed author URL"));
+        }
+        if let Some(icon_url) = self.icon_url.as_ref() {
+            context.check(url_check(icon_url, &MEDIA_SCHEMES, "Embed author icon URL"));
+        }
     }
 }
 
 impl DiscordApiCompatible for EmbedFooter {
-    fn check_compatibility(&self, _context: &mut MessageContext) -> Result<(), String> {
-        interval_check(&Self::TEXT_LEN_INTERVAL, &self.text.len(), "Embed footer text length")?;
-        Ok(())
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        context.check(interval_check(&Self::TEXT_LEN_INTERVAL, &char_count(&self.text), "Embed footer text length"));
+        if let Some(icon_url) = self.icon_url.as_ref() {
+            context.check(url_check(icon_url, &MEDIA_SCHEMES, "Embed footer icon URL"));
+        }
     }
 }
 
 impl DiscordApiCompatible for EmbedField {
-    fn check_compatibility(&self, _context: &mut MessageContext) -> Result<(), String> {
-        interval_check(&Self::VALUE_LEN_INTERVAL, &self.value.len(), "Embed field value length")?;
-        interval_check(&Self::NAME_LEN_INTERVAL, &self.name.len(), "Embed field name length")?;
-        Ok(())
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        context.check(interval_check(&Self::VALUE_LEN_INTERVAL, &char_count(&self.value), "Embed field value length"));
+        context.check(interval_check(&Self::NAME_LEN_INTERVAL, &char_count(&self.name), "Embed field name length"));
+    }
+}
+
+impl DiscordApiCompatible for SelectMenuCommonBase {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        match self.custom_id.as_ref() {
+            Some(id) => context.register_select_menu(id),
+            None => context.report("Custom ID of a select menu must be set!".to_string()),
+        }
+
+        if let Some(placeholder) = self.placeholder.as_ref() {
+            context.check(interval_check(&Self::PLACEHOLDER_LEN_INTERVAL, &char_count(placeholder), "Select menu placeholder length"));
+        }
+        if let Some(min_values) = self.min_values.as_ref() {
+            context.check(interval_check(&Self::MIN_VALUES_INTERVAL, min_values, "Select menu min values"));
+        }
+        if let Some(max_values) = self.max_values.as_ref() {
+            context.check(interval_check(&Self::MAX_VALUES_INTERVAL, max_values, "Select menu max values"));
+        }
+        if let (Some(min_values), Some(max_values)) = (self.min_values, self.max_values) {
+            if min_values > max_values {
+                context.report(format!(
+                    "Select menu min values ({}) must not exceed max values ({})",
+                    min_values, max_values
+                ));
+            }
+        }
+    }
+}
+
+impl DiscordApiCompatible for SelectMenu {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        self.select_menu_base.check_compatibility(context);
+        context.check(interval_check(&Self::OPTION_COUNT_INTERVAL, &self.options.len(), "Select menu option count"));
+        if let Some(max_values) = self.select_menu_base.max_values {
+            if max_values as usize > self.options.len() {
+                context.report(format!(
+                    "Select menu max values ({}) exceeds its option count ({})",
+                    max_values,
+                    self.options.len()
+                ));
+            }
+        }
+
+        for (index, option) in self.options.iter().enumerate() {
+            context.within(format!("options[{}]", index), |context| {
+                option.check_compatibility(context)
+            });
+        }
     }
-}
\ No newline at end of file
+}
+
+impl DiscordApiCompatible for EntitySelectMenu {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        self.select_menu_base.check_compatibility(context);
+    }
+}
+
+impl DiscordApiCompatible for SelectMenuOption {
+    fn check_compatibility(&self, context: &mut MessageContext) {
+        context.check(interval_check(&Self::LABEL_LEN_INTERVAL, &char_count(&self.label), "Select menu option label length"));
+        context.check(interval_check(&Self::VALUE_LEN_INTERVAL, &char_count(&self.value), "Select menu option value length"));
+        if let Some(description) = self.description.as_ref() {
+            context.check(interval_check(&Self::DESCRIPTION_LEN_INTERVAL, &char_count(description), "Select menu option description length"));
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::fmt::{split_text, timestamp, TimestampStyle};
+use crate::models::{Color, Embed, EmbedField, EmbedFooter, Message};
+
+/// Stamps "Page X/Y • generated at <timestamp>" footers on a set of pages.
+///
+/// Stamping overwrites the footer text of every page, so after pages are edited or appended
+/// the whole set can simply be stamped again to keep the numbering consistent.
+/// Footer icons are kept.
+///
+/// Example
+/// ```no_run
+/// # use webhook::pagination::PageFooter;
+/// # use webhook::prelude::*;
+/// # let mut messages = vec![Message::new(), Message::new(), Message::new()];
+/// PageFooter::new()
+///     .prefix("Nightly report")
+///     .stamp_messages(&mut messages);
+/// // "Nightly report • Page 1/3 • generated at <t:1700000000:f>"
+/// ```
+#[derive(Debug, Clone)]
+pub struct PageFooter {
+    prefix: Option<String>,
+    generated_at: u64,
+}
+
+impl Default for PageFooter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PageFooter {
+    /// Creates a stamp with the generation time set to now.
+    pub fn new() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        PageFooter {
+            prefix: None,
+            generated_at: now,
+        }
+    }
+
+    /// Text placed in front of the page numbers.
+    pub fn prefix(&mut self, prefix: &str) -> &mut Self {
+        self.prefix = Some(prefix.to_owned());
+        self
+    }
+
+    /// Generation time as seconds since the unix epoch.
+    pub fn generated_at(&mut self, unix_seconds: u64) -> &mut Self {
+        self.generated_at = unix_seconds;
+        self
+    }
+
+    /// The footer text of page `page` (1-based) out of `page_count`.
+    pub fn text(&self, page: usize, page_count: usize) -> String {
+        let stamp = format!(
+            "Page {}/{} • generated at {}",
+            page,
+            page_count,
+            timestamp(self.generated_at as i64, TimestampStyle::ShortDateTime)
+        );
+        match self.prefix.as_ref() {
+            Some(prefix) => format!("{} • {}", prefix, stamp),
+            None => stamp,
+        }
+    }
+
+    /// Treats every embed as a page.
+    pub fn stamp_embeds(&self, pages: &mut [Embed]) {
+        let page_count = pages.len();
+        for (index, embed) in pages.iter_mut().enumerate() {
+            self.stamp(embed, index + 1, page_count);
+        }
+    }
+
+    /// Treats every message as a page, stamping the footer of its last embed.
+    /// Messages without embeds still count as pages but are left untouched.
+    pub fn stamp_messages(&self, pages: &mut [Message]) {
+        let page_count = pages.len();
+        for (index, message) in pages.iter_mut().enumerate() {
+            if let Some(embed) = message.embeds.last_mut() {
+                self.stamp(embed, index + 1, page_count);
+            }
+        }
+    }
+
+    fn stamp(&self, embed: &mut Embed, page: usize, page_count: usize) {
+        let icon_url = embed.footer.take().and_then(|footer| footer.icon_url);
+        embed.footer = Some(EmbedFooter::new(self.text(page, page_count), icon_url));
+    }
+}
+
+/// Spreads many fields or a long description over as many embeds and messages as needed to stay
+/// within the Discord limits: 25 fields and 4096 description characters per embed, 10 embeds and
+/// 6000 embed characters per message.
+///
+/// Example
+/// ```no_run
+/// # use webhook::models::EmbedField;
+/// # use webhook::pagination::EmbedPaginator;
+/// # use webhook::prelude::*;
+/// # struct Check { name: String, summary: String }
+/// # async fn run(client: WebhookClient, results: Vec<Check>) -> WebhookResult<()> {
+/// let messages = EmbedPaginator::new()
+///     .title("Nightly report")
+///     .footer_reserve(64)
+///     .fields(results.iter().map(|r| EmbedField::new(&r.name, &r.summary, true)));
+/// for message in messages.iter() {
+///     client.send_message(message).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EmbedPaginator {
+    title: Option<String>,
+    color: Option<Color>,
+    footer_reserve: usize,
+}
+
+impl EmbedPaginator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Title of every embed.
+    pub fn title(&mut self, title: &str) -> &mut Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Color of every embed.
+    pub fn color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Characters kept free in every embed for a footer added later, e.g. by `PageFooter`.
+    pub fn footer_reserve(&mut self, chars: usize) -> &mut Self {
+        self.footer_reserve = chars;
+        self
+    }
+
+    pub fn fields<I>(&self, fields: I) -> Vec<Message>
+    where
+        I: IntoIterator<Item = EmbedField>,
+    {
+        let mut pages = Pages::new(self);
+        for field in fields {
+            pages.add_field(field);
+        }
+        pages.finish()
+    }
+
+    /// Splits the description at line breaks where possible (see `fmt::split_text`).
+    pub fn description(&self, description: &str) -> Vec<Message> {
+        let mut pages = Pages::new(self);
+        let max_chars = Embed::DESCRIPTION_LEN_INTERVAL
+            .max_allowed
+            .min(Message::EMBED_TOTAL_TEXT_LEN_INTERVAL.max_allowed - pages.embed_base_chars)
+            .max(1);
+        for chunk in split_text(description, max_chars) {
+            pages.add_description(chunk);
+        }
+        pages.finish()
+    }
+}
+
+/// The messages produced by an `EmbedPaginator`, filled embed by embed.
+struct Pages<'a> {
+    paginator: &'a EmbedPaginator,
+    messages: Vec<Message>,
+    message_chars: usize,
+    embed: Option<Embed>,
+    embed_chars: usize,
+    embed_base_chars: usize,
+}
+
+impl<'a> Pages<'a> {
+    fn new(paginator: &'a EmbedPaginator) -> Self {
+        let title_chars = paginator
+            .title
+            .as_ref()
+            .map_or(0, |title| title.chars().count());
+        Pages {
+            paginator,
+            messages: vec![],
+            message_chars: 0,
+            embed: None,
+            embed_chars: 0,
+            embed_base_chars: title_chars + paginator.footer_reserve,
+        }
+    }
+
+    fn add_field(&mut self, field: EmbedField) {
+        let chars = field.name.chars().count() + field.value.chars().count();
+        let fits = self.embed.as_ref().is_some_and(|embed| {
+            embed.fields.len() < Embed::FIELDS_LEN_INTERVAL.max_allowed
+                && self.message_chars + self.embed_chars + chars
+                    <= Message::EMBED_TOTAL_TEXT_LEN_INTERVAL.max_allowed
+        });
+        if !fits {
+            self.start_embed(chars);
+        }
+        self.embed_chars += chars;
+        self.embed.as_mut().unwrap().fields.push(field);
+    }
+
+    fn add_description(&mut self, description: String) {
+        let chars = description.chars().count();
+        self.start_embed(chars);
+        self.embed_chars += chars;
+        self.embed.as_mut().unwrap().description = Some(description);
+    }
+
+    /// Opens a new embed, in a new message if the current one cannot hold an embed with
+    /// `chars` more characters.
+    fn start_embed(&mut self, chars: usize) {
+        self.finish_embed();
+        let message_full = self.messages.last().is_none_or(|message| {
+            message.embeds.len() >= Message::EMBED_COUNT_INTERVAL.max_allowed
+                || self.message_chars + self.embed_base_chars + chars
+                    > Message::EMBED_TOTAL_TEXT_LEN_INTERVAL.max_allowed
+        });
+        if message_full {
+            self.messages.push(Message::new());
+            self.message_chars = 0;
+        }
+
+        let mut embed = Embed::new();
+        if let Some(title) = self.paginator.title.as_ref() {
+            embed.title(title);
+        }
+        embed.color = self.paginator.color;
+        self.embed = Some(embed);
+        self.embed_chars = self.embed_base_chars;
+    }
+
+    fn finish_embed(&mut self) {
+        if let Some(embed) = self.embed.take() {
+            self.message_chars += self.embed_chars;
+            self.messages.last_mut().unwrap().embeds.push(embed);
+        }
+    }
+
+    fn finish(mut self) -> Vec<Message> {
+        self.finish_embed();
+        self.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EmbedPaginator, PageFooter};
+    use crate::models::{Embed, EmbedField, Message};
+
+    fn footer_text(embed: &Embed) -> &str {
+        &embed.footer.as_ref().unwrap().text
+    }
+
+    #[test]
+    fn restamping_after_append_keeps_numbering_consistent() {
+        let mut footer = PageFooter::new();
+        footer.prefix("Report").generated_at(1_700_000_000);
+        let mut pages = vec![Embed::new(), Embed::new()];
+        pages[1].footer("old", Some(String::from("icon")));
+        footer.stamp_embeds(&mut pages);
+        assert_eq!(
+            footer_text(&pages[1]),
+            "Report • Page 2/2 • generated at <t:1700000000:f>"
+        );
+
+        pages.push(Embed::new());
+        footer.stamp_embeds(&mut pages);
+        assert_eq!(
+            footer_text(&pages[0]),
+            "Report • Page 1/3 • generated at <t:1700000000:f>"
+        );
+        assert_eq!(
+            pages[1].footer.as_ref().unwrap().icon_url.as_deref(),
+            Some("icon")
+        );
+    }
+
+    #[test]
+    fn paginated_messages_are_valid() {
+        let short = (0..100).map(|i| EmbedField::new(i.to_string(), "value", true));
+        let messages = EmbedPaginator::new().title("Report").fields(short);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].embeds.len(), 4);
+        assert_eq!(messages[0].embeds[3].fields.len(), 25);
+
+        let long = (0..30).map(|i| EmbedField::new(i.to_string(), "v".repeat(1024), false));
+        let mut paginator = EmbedPaginator::new();
+        paginator.title("Report").footer_reserve(64);
+        let messages = paginator.fields(long);
+        assert!(messages.len() > 1);
+        let field_count: usize = messages
+            .iter()
+            .flat_map(|message| message.embeds.iter())
+            .map(|embed| embed.fields.len())
+            .sum();
+        assert_eq!(field_count, 30);
+
+        let messages = EmbedPaginator::new().description(&"line\n".repeat(5000));
+        assert!(messages.len() > 1);
+
+        for message in messages.iter() {
+            message.validate().unwrap();
+        }
+        let mut stamped: Vec<Message> = paginator
+            .fields((0..30).map(|i| EmbedField::new(i.to_string(), "v".repeat(1024), false)));
+        PageFooter::new().stamp_messages(&mut stamped);
+        for message in stamped.iter() {
+            message.validate().unwrap();
+        }
+    }
+}
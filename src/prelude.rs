@@ -0,0 +1,42 @@
+//! The commonly used types, for glob importing.
+//!
+//! ```no_run
+//! use webhook::prelude::*;
+//! ```
+
+pub use crate::avatar::Avatar;
+pub use crate::builder::{EmbedBuilder, MessageBuilder};
+pub use crate::models::{
+    ActionRow, AllowedMentions, ChannelType, Color, Embed, IntoEmbed, IntoMessage, Message,
+    NonLinkButtonStyle, SeparatorSpacing, Snowflake, Timestamp, ValidationError, ValidationReport,
+    Webhook, WebhookEdit, WebhookType,
+};
+pub use crate::url::WebhookUrl;
+pub use crate::WebhookResult;
+
+#[cfg(feature = "request")]
+pub use crate::request::{InvalidToken, NotFound};
+
+#[cfg(feature = "image")]
+pub use crate::images::{ImageFormat, ImagePolicy};
+
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub use crate::breaker::CircuitOpen;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub use crate::broadcast::WebhookBroadcaster;
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub use crate::client::{
+    Cancelled, LintLevel, ValidationMode, WebhookClient, WebhookClientBuilder,
+};
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub use crate::manager::WebhookManager;
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub use crate::ratelimit::RateLimiter;
+#[cfg(any(feature = "client", feature = "async-io"))]
+pub use crate::retry::{RetryOn, RetryPolicy};
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub use crate::router::{Severity, WebhookRouter};
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub use crate::transport::{BodyTooLarge, Timeout};
+#[cfg(feature = "client")]
+pub use crate::upload::FileStream;
@@ -0,0 +1,921 @@
+use futures_util::future::BoxFuture;
+use futures_util::Sink;
+use http::{HeaderMap, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+#[cfg(feature = "journal")]
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::mpsc::error::SendError;
+use tokio::sync::mpsc::OwnedPermit;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::client::{WebhookClient, WebhookError, WebhookResult};
+#[cfg(feature = "journal")]
+use crate::journal::Journal;
+use crate::models::{Embed, EmbedField, Message};
+use crate::request::{header_seconds, retry_after};
+#[cfg(feature = "metrics")]
+use crate::telemetry;
+use crate::transport::HttpResponse;
+
+/// A handle to a background task that sends queued messages in order.
+///
+/// Handles are cheap to clone; the task stops once every handle is dropped and the queued
+/// messages are sent, or after `shutdown`. The task respects the rate limit headers of the webhook, waits out
+/// `429 Too Many Requests` responses and retries server errors and network failures.
+/// A message that still fails after the retries is dropped.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # use webhook::queue::WebhookQueue;
+/// # async fn run(url: &str) -> WebhookResult<()> {
+/// let queue = WebhookQueue::builder().capacity(256).spawn(WebhookClient::new(url));
+/// let mut message = Message::new();
+/// message.content("disk usage above 90%");
+/// queue.enqueue(message).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct WebhookQueue {
+    /// Checks the messages as they are queued, see `ValidationMode`.
+    client: WebhookClient,
+    sender: mpsc::Sender<Job>,
+    worker: Arc<WorkerHandle>,
+    #[cfg(feature = "journal")]
+    journal: Option<Arc<Journal>>,
+}
+
+enum Job {
+    /// A message and its journal id.
+    Send(Box<Message>, Option<u64>),
+    Flush(oneshot::Sender<()>),
+    Shutdown,
+}
+
+impl WebhookQueue {
+    /// Starts a queue with the default settings. Must be called within a tokio runtime.
+    pub fn spawn(client: WebhookClient) -> Self {
+        Self::builder().spawn(client)
+    }
+
+    pub fn builder() -> WebhookQueueBuilder {
+        WebhookQueueBuilder::new()
+    }
+
+    /// Validates the message like `WebhookClient::send_message` does, see `ValidationMode`, and
+    /// queues it, waiting for room in the queue if it is full.
+    pub async fn enqueue(&self, message: Message) -> WebhookResult<()> {
+        let message = self.client.checked(message)?;
+        let permit = self
+            .sender
+            .reserve()
+            .await
+            .map_err(|_| "The queue worker has stopped")?;
+        permit.send(self.send_job(message)?);
+        Ok(())
+    }
+
+    /// Validates the message like `enqueue` and queues it, failing instead of waiting if the
+    /// queue is full.
+    pub fn try_enqueue(&self, message: Message) -> WebhookResult<()> {
+        let message = self.client.checked(message)?;
+        let permit = self.sender.try_reserve().map_err(|err| match err {
+            mpsc::error::TrySendError::Full(_) => "The queue is full",
+            mpsc::error::TrySendError::Closed(_) => "The queue worker has stopped",
+        })?;
+        permit.send(self.send_job(message)?);
+        Ok(())
+    }
+
+    /// A `Sink` queueing the messages sent into it, so a `futures` stream of messages can end
+    /// in the queue, e.g. with `stream.map(Ok).forward(queue.sink())`.
+    pub fn sink(&self) -> QueueSink {
+        QueueSink {
+            queue: self.clone(),
+            reserving: None,
+            permit: None,
+            flushing: None,
+        }
+    }
+
+    /// Journals the message if the queue has a journal.
+    fn send_job(&self, message: Message) -> WebhookResult<Job> {
+        #[cfg(feature = "journal")]
+        if let Some(journal) = self.journal.as_ref() {
+            let id = journal.add(serde_json::to_value(&message)?)?;
+            return Ok(Job::Send(Box::new(message), Some(id)));
+        }
+        Ok(Job::Send(Box::new(message), None))
+    }
+}
+
+impl WebhookQueue {
+    /// Waits until every message queued before the call has been sent (or given up on).
+    pub async fn flush(&self) -> WebhookResult<()> {
+        let (done, flushed) = oneshot::channel();
+        self.sender
+            .send(Job::Flush(done))
+            .await
+            .map_err(|_| "The queue worker has stopped")?;
+        flushed
+            .await
+            .map_err(|_| "The queue worker has stopped".into())
+    }
+
+    /// Stops accepting messages and waits up to `timeout` for the queued ones to be sent.
+    ///
+    /// Fails if the queue could not be drained in time; the remaining messages are dropped.
+    /// Enqueueing through any handle fails after a shutdown.
+    ///
+    /// Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use webhook::queue::WebhookQueue;
+    /// # async fn run(queue: WebhookQueue) -> webhook::WebhookResult<()> {
+    /// tokio::signal::ctrl_c().await?;
+    /// queue.shutdown(Duration::from_secs(5)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> WebhookResult<()> {
+        let worker = match self.worker.task.lock().unwrap().take() {
+            Some(worker) => worker,
+            None => return Ok(()),
+        };
+        stop_worker(&self.sender, worker, timeout).await
+    }
+}
+
+/// The worker task, shared by every handle of a queue.
+struct WorkerHandle {
+    task: Mutex<Option<JoinHandle<()>>>,
+    /// Set by `WebhookQueueBuilder::flush_on_drop`.
+    flush_on_drop: Option<(mpsc::Sender<Job>, Duration)>,
+}
+
+impl WorkerHandle {
+    fn new(task: JoinHandle<()>, flush_on_drop: Option<(mpsc::Sender<Job>, Duration)>) -> Self {
+        WorkerHandle {
+            task: Mutex::new(Some(task)),
+            flush_on_drop,
+        }
+    }
+}
+
+impl Drop for WorkerHandle {
+    /// Runs when the last handle of the queue is dropped.
+    fn drop(&mut self) {
+        let (sender, timeout) = match self.flush_on_drop.take() {
+            Some(flush_on_drop) => flush_on_drop,
+            None => return,
+        };
+        let task = match self.task.get_mut() {
+            Ok(task) => task.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        let task = match task {
+            Some(task) if !task.is_finished() && !std::thread::panicking() => task,
+            _ => return,
+        };
+        // waiting blocks the thread, so another thread has to run the worker meanwhile
+        let runtime = match Handle::try_current() {
+            Ok(runtime) if runtime.runtime_flavor() == RuntimeFlavor::MultiThread => runtime,
+            _ => return,
+        };
+        let _ =
+            tokio::task::block_in_place(|| runtime.block_on(stop_worker(&sender, task, timeout)));
+    }
+}
+
+/// Lets the worker send the queued messages and waits up to `timeout` for it to stop.
+async fn stop_worker(
+    sender: &mpsc::Sender<Job>,
+    mut worker: JoinHandle<()>,
+    timeout: Duration,
+) -> WebhookResult<()> {
+    // the worker only stops on its own once every handle is dropped, so it is still running
+    let _ = sender.send(Job::Shutdown).await;
+
+    match tokio::time::timeout(timeout, &mut worker).await {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            worker.abort();
+            Err("The queue was not drained before the shutdown timeout".into())
+        }
+    }
+}
+
+/// A `Sink` of messages feeding a `WebhookQueue`, see `WebhookQueue::sink`.
+///
+/// The sink is ready once the queue has room for a message, and sending fails for messages
+/// that do not pass validation. Closing the sink waits until the queued messages are sent.
+pub struct QueueSink {
+    queue: WebhookQueue,
+    reserving: Option<BoxFuture<'static, Result<OwnedPermit<Job>, SendError<()>>>>,
+    /// Room in the queue for the next message.
+    permit: Option<OwnedPermit<Job>>,
+    flushing: Option<BoxFuture<'static, WebhookResult<()>>>,
+}
+
+impl Sink<Message> for QueueSink {
+    type Error = Box<WebhookError>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<WebhookResult<()>> {
+        let sink = self.get_mut();
+        if sink.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        let sender = sink.queue.sender.clone();
+        let reserving = sink
+            .reserving
+            .get_or_insert_with(|| Box::pin(sender.reserve_owned()));
+        let permit = match reserving.as_mut().poll(cx) {
+            Poll::Ready(permit) => permit,
+            Poll::Pending => return Poll::Pending,
+        };
+        sink.reserving = None;
+        sink.permit = Some(permit.map_err(|_| "The queue worker has stopped")?);
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, message: Message) -> WebhookResult<()> {
+        let sink = self.get_mut();
+        let message = sink.queue.client.checked(message)?;
+        let permit = sink
+            .permit
+            .take()
+            .ok_or("The sink was not ready for another message")?;
+        permit.send(sink.queue.send_job(message)?);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<WebhookResult<()>> {
+        // the messages are in the queue as soon as they were sent into the sink
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<WebhookResult<()>> {
+        let sink = self.get_mut();
+        sink.reserving = None;
+        sink.permit = None;
+        let queue = sink.queue.clone();
+        let flushing = sink
+            .flushing
+            .get_or_insert_with(|| Box::pin(async move { queue.flush().await }));
+        let flushed = futures_util::ready!(flushing.as_mut().poll(cx));
+        sink.flushing = None;
+        Poll::Ready(flushed)
+    }
+}
+
+/// Settings of a `WebhookQueue`.
+pub struct WebhookQueueBuilder {
+    capacity: usize,
+    max_retries: u32,
+    retry_delay: Duration,
+    coalescing: Option<Coalescing>,
+    flush_on_drop: Option<Duration>,
+}
+
+type CoalescingKey = dyn Fn(&Message) -> Option<String> + Send + Sync;
+
+#[derive(Clone)]
+struct Coalescing {
+    window: Duration,
+    key: Arc<CoalescingKey>,
+}
+
+impl WebhookQueueBuilder {
+    fn new() -> Self {
+        WebhookQueueBuilder {
+            capacity: 100,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+            coalescing: None,
+            flush_on_drop: None,
+        }
+    }
+
+    /// Number of messages the queue holds before `enqueue` waits. Defaults to 100.
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// How often a message is retried after a server error or network failure. Defaults to 3.
+    /// Rate limited attempts are always retried and do not count.
+    pub fn max_retries(&mut self, max_retries: u32) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry, doubled for every further retry. Defaults to 500ms.
+    pub fn retry_delay(&mut self, retry_delay: Duration) -> &mut Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Merges bursts of similar messages into one.
+    ///
+    /// Messages for which `key` returns the same key within `window` of the first one are counted
+    /// instead of sent. When the window ends, the first message is sent, annotated with the
+    /// number of occurrences: as an "Occurrences" field on its last embed, or a line appended to
+    /// the content if it has no embed with room for a field. Coalesced messages are therefore
+    /// delayed by up to `window` and may overtake other messages. Messages with key `None` are
+    /// sent as usual.
+    ///
+    /// Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use webhook::client::WebhookClient;
+    /// # use webhook::queue::WebhookQueue;
+    /// # async fn run(client: WebhookClient) {
+    /// WebhookQueue::builder()
+    ///     .coalesce(Duration::from_secs(60), |message| message.content.clone())
+    ///     .spawn(client);
+    /// # }
+    /// ```
+    pub fn coalesce<Key>(&mut self, window: Duration, key: Key) -> &mut Self
+    where
+        Key: Fn(&Message) -> Option<String> + Send + Sync + 'static,
+    {
+        self.coalescing = Some(Coalescing {
+            window,
+            key: Arc::new(key),
+        });
+        self
+    }
+
+    /// Sends the queued messages when the last handle of the queue is dropped, waiting up to
+    /// `timeout` like `WebhookQueue::shutdown`, for programs that exit without shutting the
+    /// queue down. Off by default.
+    ///
+    /// Waiting blocks the dropping thread, so this only takes effect in a multi-threaded tokio
+    /// runtime, and not while the thread is panicking.
+    pub fn flush_on_drop(&mut self, timeout: Duration) -> &mut Self {
+        self.flush_on_drop = Some(timeout);
+        self
+    }
+
+    /// Starts the worker task. Must be called within a tokio runtime.
+    pub fn spawn(&self, client: WebhookClient) -> WebhookQueue {
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        let worker = self.worker(client.clone());
+        let task = tokio::spawn(worker.run(receiver, vec![]));
+        WebhookQueue {
+            client,
+            worker: Arc::new(self.worker_handle(task, &sender)),
+            sender,
+            #[cfg(feature = "journal")]
+            journal: None,
+        }
+    }
+
+    /// Starts the worker task with a journal at `path`, for at-least-once delivery across
+    /// restarts. Must be called within a tokio runtime.
+    ///
+    /// Messages are written to the journal before `enqueue` returns and removed once they
+    /// were delivered or rejected by Discord. Messages left in the journal by an earlier run
+    /// are sent first, so a message may be sent twice if the process stopped right after
+    /// sending it. Messages that still fail after the retries stay in the journal until the
+    /// next start. Redaction rules are applied when sending, so the journal holds the
+    /// unredacted text. The contents of attached files are not journaled, so Discord rejects
+    /// a replayed message that had files.
+    ///
+    /// Example
+    /// ```no_run
+    /// # use webhook::prelude::*;
+    /// # use webhook::queue::WebhookQueue;
+    /// # const URL: &str = "https://discord.com/api/webhooks/0/token";
+    /// # async fn run() -> WebhookResult<()> {
+    /// let queue = WebhookQueue::builder()
+    ///     .spawn_with_journal(WebhookClient::new(URL), "/var/lib/my-service/alerts.journal")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "journal")]
+    pub fn spawn_with_journal<P: AsRef<Path>>(
+        &self,
+        client: WebhookClient,
+        path: P,
+    ) -> WebhookResult<WebhookQueue> {
+        let (journal, pending) = Journal::open(path.as_ref())?;
+        let journal = Arc::new(journal);
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        let mut worker = self.worker(client.clone());
+        worker.journal = Some(journal.clone());
+        let task = tokio::spawn(worker.run(receiver, pending));
+        Ok(WebhookQueue {
+            client,
+            worker: Arc::new(self.worker_handle(task, &sender)),
+            sender,
+            journal: Some(journal),
+        })
+    }
+
+    fn worker_handle(&self, task: JoinHandle<()>, sender: &mpsc::Sender<Job>) -> WorkerHandle {
+        let flush_on_drop = self.flush_on_drop.map(|timeout| (sender.clone(), timeout));
+        WorkerHandle::new(task, flush_on_drop)
+    }
+
+    fn worker(&self, client: WebhookClient) -> Worker {
+        Worker {
+            client,
+            max_retries: self.max_retries,
+            retry_delay: self.retry_delay,
+            coalescing: self.coalescing.clone(),
+            bursts: HashMap::new(),
+            #[cfg(feature = "journal")]
+            journal: None,
+        }
+    }
+}
+
+struct Worker {
+    client: WebhookClient,
+    max_retries: u32,
+    retry_delay: Duration,
+    coalescing: Option<Coalescing>,
+    bursts: HashMap<String, Burst>,
+    #[cfg(feature = "journal")]
+    journal: Option<Arc<Journal>>,
+}
+
+/// The first message of a burst of coalesced messages.
+struct Burst {
+    message: Message,
+    count: usize,
+    deadline: Instant,
+    journal_ids: Vec<u64>,
+}
+
+/// How sending a message ended.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    Sent,
+    /// Discord rejected the message, sending it again would not help.
+    Rejected,
+    GaveUp,
+}
+
+impl Worker {
+    /// Sends the messages journaled by an earlier run, then the queued ones.
+    async fn run(mut self, mut receiver: mpsc::Receiver<Job>, replay: Vec<(u64, Value)>) {
+        for (journal_id, payload) in replay {
+            let delivery = self.deliver(|| self.client.execute_payload(&payload)).await;
+            self.settle(&[journal_id], delivery);
+        }
+
+        loop {
+            let next_deadline = self.bursts.values().map(|burst| burst.deadline).min();
+            let job = match next_deadline {
+                Some(deadline) => tokio::select! {
+                    job = receiver.recv() => job,
+                    _ = tokio::time::sleep_until(deadline) => {
+                        self.release_bursts(Some(Instant::now())).await;
+                        continue;
+                    }
+                },
+                None => receiver.recv().await,
+            };
+
+            match job {
+                Some(Job::Send(message, journal_id)) => self.process(*message, journal_id).await,
+                Some(Job::Flush(done)) => {
+                    self.release_bursts(None).await;
+                    let _ = done.send(());
+                }
+                // rejects new jobs, the ones already queued are still processed
+                Some(Job::Shutdown) => receiver.close(),
+                None => break,
+            }
+        }
+        self.release_bursts(None).await;
+    }
+
+    async fn process(&mut self, message: Message, journal_id: Option<u64>) {
+        let burst = self.coalescing.as_ref().and_then(|coalescing| {
+            let key = (coalescing.key)(&message)?;
+            Some((key, Instant::now() + coalescing.window))
+        });
+
+        match burst {
+            Some((key, deadline)) => {
+                let burst = self.bursts.entry(key).or_insert(Burst {
+                    message,
+                    count: 0,
+                    deadline,
+                    journal_ids: vec![],
+                });
+                burst.count += 1;
+                burst.journal_ids.extend(journal_id);
+            }
+            None => {
+                // failures are dropped, the caller was released when the message was queued
+                let delivery = self.send(&message).await;
+                self.settle(journal_id.as_slice(), delivery);
+            }
+        }
+    }
+
+    /// Sends the bursts whose window ended by `now`, or all bursts if `now` is `None`.
+    async fn release_bursts(&mut self, now: Option<Instant>) {
+        let due: Vec<String> = self
+            .bursts
+            .iter()
+            .filter(|(_, burst)| now.is_none_or(|now| burst.deadline <= now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut due: Vec<Burst> = due
+            .iter()
+            .filter_map(|key| self.bursts.remove(key))
+            .collect();
+        due.sort_by_key(|burst| burst.deadline);
+
+        for mut burst in due {
+            if burst.count > 1 {
+                annotate_occurrences(&mut burst.message, burst.count);
+            }
+            let delivery = self.send(&burst.message).await;
+            self.settle(&burst.journal_ids, delivery);
+        }
+    }
+
+    async fn send(&self, message: &Message) -> Delivery {
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let delivery = match self.client.download_attachments(message).await {
+            Ok(message) => match self.client.execution_body(&message) {
+                Ok((content_type, body)) => {
+                    self.deliver(|| self.client.execute_body(&content_type, &body))
+                        .await
+                }
+                Err(_) => Delivery::Rejected,
+            },
+            Err(_) => Delivery::Rejected,
+        };
+        #[cfg(feature = "metrics")]
+        telemetry::delivered("queue", delivery == Delivery::Sent, started);
+        delivery
+    }
+
+    async fn deliver<Execute, Fut>(&self, execute: Execute) -> Delivery
+    where
+        Execute: Fn() -> Fut,
+        Fut: Future<Output = WebhookResult<HttpResponse>>,
+    {
+        let mut retries = 0;
+        loop {
+            // server errors and network failures are retried
+            match execute().await {
+                Ok(response) if response.status().is_success() => {
+                    if let Some(wait) = bucket_exhausted(response.headers()) {
+                        tokio::time::sleep(wait).await;
+                    }
+                    return Delivery::Sent;
+                }
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    #[cfg(feature = "metrics")]
+                    telemetry::retried("queue");
+                    tokio::time::sleep(retry_after(&response)).await;
+                    continue;
+                }
+                Ok(response) if !response.status().is_server_error() => return Delivery::Rejected,
+                Ok(_) | Err(_) => {}
+            }
+
+            if retries >= self.max_retries {
+                return Delivery::GaveUp;
+            }
+            #[cfg(feature = "metrics")]
+            telemetry::retried("queue");
+            tokio::time::sleep(self.retry_delay * 2u32.saturating_pow(retries)).await;
+            retries += 1;
+        }
+    }
+}
+
+impl Worker {
+    /// Removes messages from the journal once sending them again would not help.
+    #[cfg_attr(not(feature = "journal"), allow(unused_variables))]
+    fn settle(&self, journal_ids: &[u64], delivery: Delivery) {
+        #[cfg(feature = "journal")]
+        if let Some(journal) = self.journal.as_ref() {
+            if delivery != Delivery::GaveUp {
+                for journal_id in journal_ids {
+                    let _ = journal.done(*journal_id);
+                }
+            }
+        }
+    }
+}
+
+/// Notes how often a coalesced message occurred, in a field of its last embed or else in its
+/// content. The message is sent without the note if it would exceed the limits.
+fn annotate_occurrences(message: &mut Message, count: usize) {
+    const NAME: &str = "Occurrences";
+    let text = format!("Occurred {} times", count);
+    let field_fits = NAME.len() + text.len() <= message.remaining_embed_chars();
+    match message.embeds.last_mut() {
+        Some(embed) if embed.fields.len() < Embed::FIELDS_LEN_INTERVAL.max_allowed && field_fits => {
+            embed.fields.push(EmbedField::new(NAME, &text, true));
+        }
+        _ => {
+            let content = message.content.get_or_insert_with(String::new);
+            let note = if content.is_empty() {
+                format!("({})", text)
+            } else {
+                format!("\n({})", text)
+            };
+            if content.chars().count() + note.len() <= Message::CONTENT_LEN_INTERVAL.max_allowed {
+                content.push_str(&note);
+            }
+        }
+    }
+}
+
+/// How long to wait before the next request if the response used up the rate limit bucket.
+fn bucket_exhausted(headers: &HeaderMap) -> Option<Duration> {
+    let remaining = headers.get("x-ratelimit-remaining")?.to_str().ok()?;
+    if remaining == "0" {
+        header_seconds(headers, "x-ratelimit-reset-after")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebhookQueue;
+    use crate::client::{ValidationMode, WebhookClient};
+    use crate::models::Message;
+    use crate::testing::MockTransport;
+    use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+    use crate::WebhookResult;
+    use futures_util::future::BoxFuture;
+    use futures_util::stream::{self, StreamExt};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Answers with the scripted statuses in order, then with 204.
+    struct ScriptedTransport {
+        statuses: Mutex<Vec<u16>>,
+        contents: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl WebhookTransport for ScriptedTransport {
+        fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+            let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+            self.contents
+                .lock()
+                .unwrap()
+                .push(body["content"].as_str().unwrap().to_owned());
+            let status = self.statuses.lock().unwrap().pop().unwrap_or(204);
+            Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(status)
+                    .body(br#"{"retry_after": 0.01}"#.to_vec())?)
+            })
+        }
+    }
+
+    fn message(content: &str) -> Message {
+        let mut message = Message::new();
+        message.content(content);
+        message
+    }
+
+    #[tokio::test]
+    async fn messages_are_sent_in_order_with_retries() {
+        let contents = Arc::new(Mutex::new(vec![]));
+        let transport = ScriptedTransport {
+            // popped from the back: the first attempt is rate limited, the second fails
+            statuses: Mutex::new(vec![500, 429]),
+            contents: contents.clone(),
+        };
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(transport)
+            .build()
+            .unwrap();
+        let queue = WebhookQueue::builder()
+            .retry_delay(Duration::from_millis(1))
+            .spawn(client);
+
+        queue.enqueue(message("first")).await.unwrap();
+        queue.enqueue(message("second")).await.unwrap();
+        assert!(queue.enqueue(message(&"a".repeat(2001))).await.is_err());
+
+        queue.flush().await.unwrap();
+        assert_eq!(
+            *contents.lock().unwrap(),
+            vec!["first", "first", "first", "second"]
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_the_queue() {
+        let contents = Arc::new(Mutex::new(vec![]));
+        let transport = ScriptedTransport {
+            statuses: Mutex::new(vec![]),
+            contents: contents.clone(),
+        };
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(transport)
+            .build()
+            .unwrap();
+        let queue = WebhookQueue::spawn(client);
+        let handle = queue.clone();
+
+        for index in 0..10 {
+            handle.enqueue(message(&index.to_string())).await.unwrap();
+        }
+        queue.shutdown(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(contents.lock().unwrap().len(), 10);
+        assert!(handle.enqueue(message("late")).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dropping_the_queue_sends_the_queued_messages() {
+        let mock = MockTransport::new();
+        let queue = WebhookQueue::builder()
+            .flush_on_drop(Duration::from_secs(5))
+            .spawn(mock.client());
+        let handle = queue.clone();
+
+        for index in 0..10 {
+            queue.enqueue(message(&index.to_string())).await.unwrap();
+        }
+        drop(queue);
+        // another handle keeps the queue open
+        handle.enqueue(message("last")).await.unwrap();
+        drop(handle);
+        assert_eq!(mock.messages().len(), 11);
+    }
+
+    #[tokio::test]
+    async fn streams_are_forwarded_into_the_queue() {
+        let contents = Arc::new(Mutex::new(vec![]));
+        let transport = ScriptedTransport {
+            statuses: Mutex::new(vec![]),
+            contents: contents.clone(),
+        };
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(transport)
+            .build()
+            .unwrap();
+        let queue = WebhookQueue::builder().capacity(2).spawn(client);
+
+        let messages = stream::iter(0..5).map(|index| Ok(message(&index.to_string())));
+        messages.forward(queue.sink()).await.unwrap();
+        // closing the sink waited for the messages to be sent
+        assert_eq!(*contents.lock().unwrap(), vec!["0", "1", "2", "3", "4"]);
+
+        let invalid = stream::iter(vec![Ok(message(&"a".repeat(2001)))]);
+        assert!(invalid.forward(queue.sink()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn queued_messages_are_checked_in_the_validation_mode_of_the_client() {
+        let mock = MockTransport::new();
+        let client = mock
+            .builder()
+            .validation_mode(ValidationMode::Truncate)
+            .build()
+            .unwrap();
+        let queue = WebhookQueue::spawn(client);
+
+        queue.enqueue(message(&"a".repeat(2500))).await.unwrap();
+        queue.try_enqueue(message(&"b".repeat(2500))).unwrap();
+        let messages = stream::iter(vec![Ok(message(&"c".repeat(2500)))]);
+        messages.forward(queue.sink()).await.unwrap();
+
+        let contents: Vec<usize> = mock
+            .messages()
+            .iter()
+            .map(|message| message.content.as_ref().unwrap().chars().count())
+            .collect();
+        assert_eq!(contents, vec![2000, 2000, 2000]);
+    }
+
+    #[tokio::test]
+    async fn bursts_are_coalesced() {
+        let contents = Arc::new(Mutex::new(vec![]));
+        let transport = ScriptedTransport {
+            statuses: Mutex::new(vec![]),
+            contents: contents.clone(),
+        };
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(transport)
+            .build()
+            .unwrap();
+        let queue = WebhookQueue::builder()
+            .coalesce(Duration::from_millis(50), |message| {
+                message
+                    .content
+                    .as_ref()
+                    .filter(|content| content.starts_with("error"))
+                    .cloned()
+            })
+            .spawn(client);
+
+        for _ in 0..5 {
+            queue.enqueue(message("error: disk full")).await.unwrap();
+        }
+        queue.enqueue(message("deployed")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        queue.enqueue(message("error: disk full")).await.unwrap();
+        queue.flush().await.unwrap();
+
+        assert_eq!(
+            *contents.lock().unwrap(),
+            vec![
+                "deployed",
+                "error: disk full\n(Occurred 5 times)",
+                "error: disk full"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn occurrences_are_only_noted_within_the_limits() {
+        let mock = MockTransport::new();
+        let queue = WebhookQueue::builder()
+            .coalesce(Duration::from_secs(60), |message| {
+                Some(message.content.clone().unwrap_or_default())
+            })
+            .spawn(mock.client());
+
+        let full = "a".repeat(2000);
+        let mut embedded = message("disk full");
+        embedded.embed(|embed| embed.description("b".repeat(4096)));
+        embedded.embed(|embed| embed.description("c".repeat(1900)));
+        for _ in 0..2 {
+            queue.enqueue(message(&full)).await.unwrap();
+            queue.enqueue(embedded.clone()).await.unwrap();
+        }
+        queue.flush().await.unwrap();
+
+        let messages = mock.messages();
+        assert_eq!(messages[0].content.as_deref(), Some(full.as_str()));
+        // the field does not fit into the 6000 characters of the embeds
+        assert_eq!(
+            messages[1].content.as_deref(),
+            Some("disk full\n(Occurred 2 times)")
+        );
+        assert!(messages[1].embeds[1].fields.is_empty());
+        assert!(messages.iter().all(|message| message.validate().is_ok()));
+    }
+
+    #[cfg(feature = "journal")]
+    #[tokio::test]
+    async fn journaled_messages_are_sent_after_a_restart() {
+        let path = std::env::temp_dir().join(format!("webhook-queue-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let contents = Arc::new(Mutex::new(vec![]));
+        let failing = ScriptedTransport {
+            statuses: Mutex::new(vec![500, 500]),
+            contents: contents.clone(),
+        };
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(failing)
+            .build()
+            .unwrap();
+        let queue = WebhookQueue::builder()
+            .max_retries(0)
+            .spawn_with_journal(client, &path)
+            .unwrap();
+        queue.enqueue(message("lost")).await.unwrap();
+        queue.enqueue(message("kept")).await.unwrap();
+        queue.shutdown(Duration::from_secs(5)).await.unwrap();
+
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(ScriptedTransport {
+                statuses: Mutex::new(vec![]),
+                contents: contents.clone(),
+            })
+            .build()
+            .unwrap();
+        let queue = WebhookQueue::builder()
+            .spawn_with_journal(client, &path)
+            .unwrap();
+        queue.enqueue(message("new")).await.unwrap();
+        queue.shutdown(Duration::from_secs(5)).await.unwrap();
+
+        assert_eq!(
+            *contents.lock().unwrap(),
+            vec!["lost", "kept", "lost", "kept", "new"]
+        );
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::transport::sleep;
+
+/// A rate limiter that can be shared by several `WebhookClient`s.
+///
+/// Requests are admitted by token buckets: one per webhook id and one shared by all
+/// webhooks. Clients that are built with the same limiter (see
+/// `WebhookClientBuilder::rate_limiter`) wait for a free token before each request,
+/// so a burst spread over many clients still stays within the configured quota.
+///
+/// The handle is cheap to clone; all clones share the same buckets.
+///
+/// Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use webhook::prelude::*;
+/// # async fn run() -> WebhookResult<()> {
+/// let limiter = RateLimiter::builder()
+///     .per_webhook(5, Duration::from_secs(2))
+///     .global(30, Duration::from_secs(1))
+///     .build();
+/// let alerts = WebhookClient::builder("URL").rate_limiter(&limiter).build()?;
+/// let audit = WebhookClient::builder("URL").rate_limiter(&limiter).build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    per_webhook: Quota,
+    global: Option<Bucket>,
+    webhooks: HashMap<String, Bucket>,
+}
+
+/// `requests` tokens that are refilled evenly over `period`.
+#[derive(Debug, Clone, Copy)]
+struct Quota {
+    requests: u32,
+    period: Duration,
+}
+
+impl Quota {
+    fn new(requests: u32, period: Duration) -> Self {
+        Self {
+            requests: requests.max(1),
+            period,
+        }
+    }
+
+    fn refill_rate(&self) -> f64 {
+        self.requests as f64 / self.period.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    quota: Quota,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn full(quota: Quota, now: Instant) -> Self {
+        Self {
+            quota,
+            tokens: quota.requests as f64,
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.quota.refill_rate())
+            .min(self.quota.requests as f64);
+        self.refilled_at = now;
+    }
+
+    /// How long until a whole token is available, zero if one is available now.
+    fn wait_time(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.quota.refill_rate())
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter with Discord's documented webhook limit of
+    /// 5 requests per 2 seconds for each webhook and 50 requests per second in total.
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> RateLimiterBuilder {
+        RateLimiterBuilder::new()
+    }
+
+    /// Waits until a request to the webhook identified by `key` is admitted and takes
+    /// its tokens.
+    pub(crate) async fn acquire(&self, key: &str) {
+        loop {
+            let wait = self.try_acquire(key, Instant::now());
+            if wait == Duration::from_secs(0) {
+                return;
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                webhook = key,
+                wait_ms = wait.as_millis() as u64,
+                "waiting for the rate limiter"
+            );
+            sleep(wait).await;
+        }
+    }
+
+    /// Takes the tokens for a request if both buckets have one, otherwise returns how long
+    /// to wait before trying again.
+    fn try_acquire(&self, key: &str, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let quota = state.per_webhook;
+        let State {
+            global, webhooks, ..
+        } = &mut *state;
+        let webhook = webhooks
+            .entry(key.to_owned())
+            .or_insert_with(|| Bucket::full(quota, now));
+
+        webhook.refill(now);
+        let mut wait = webhook.wait_time();
+        if let Some(global) = global.as_mut() {
+            global.refill(now);
+            wait = wait.max(global.wait_time());
+        }
+
+        if wait == Duration::from_secs(0) {
+            webhook.tokens -= 1.0;
+            if let Some(global) = global.as_mut() {
+                global.tokens -= 1.0;
+            }
+        }
+        wait
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for a `RateLimiter`.
+pub struct RateLimiterBuilder {
+    per_webhook: Quota,
+    global: Option<Quota>,
+}
+
+impl RateLimiterBuilder {
+    fn new() -> Self {
+        Self {
+            per_webhook: Quota::new(5, Duration::from_secs(2)),
+            global: Some(Quota::new(50, Duration::from_secs(1))),
+        }
+    }
+
+    /// At most `requests` requests per `period` to each webhook.
+    pub fn per_webhook(&mut self, requests: u32, period: Duration) -> &mut Self {
+        self.per_webhook = Quota::new(requests, period);
+        self
+    }
+
+    /// At most `requests` requests per `period` across all webhooks.
+    pub fn global(&mut self, requests: u32, period: Duration) -> &mut Self {
+        self.global = Some(Quota::new(requests, period));
+        self
+    }
+
+    /// Only limits requests per webhook.
+    pub fn no_global_limit(&mut self) -> &mut Self {
+        self.global = None;
+        self
+    }
+
+    pub fn build(&self) -> RateLimiter {
+        let now = Instant::now();
+        RateLimiter {
+            state: Arc::new(Mutex::new(State {
+                per_webhook: self.per_webhook,
+                global: self.global.map(|quota| Bucket::full(quota, now)),
+                webhooks: HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn webhooks_have_separate_buckets() {
+        let limiter = RateLimiter::builder()
+            .per_webhook(2, Duration::from_secs(2))
+            .no_global_limit()
+            .build();
+        let now = Instant::now();
+
+        assert_eq!(limiter.try_acquire("1", now), Duration::from_secs(0));
+        assert_eq!(limiter.try_acquire("1", now), Duration::from_secs(0));
+        assert_eq!(limiter.try_acquire("1", now), Duration::from_secs(1));
+        assert_eq!(limiter.try_acquire("2", now), Duration::from_secs(0));
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(limiter.try_acquire("1", later), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn global_bucket_is_shared() {
+        let limiter = RateLimiter::builder()
+            .per_webhook(5, Duration::from_secs(1))
+            .global(3, Duration::from_secs(3))
+            .build();
+        let now = Instant::now();
+
+        for key in &["1", "2", "3"] {
+            assert_eq!(limiter.try_acquire(key, now), Duration::from_secs(0));
+        }
+        assert!(limiter.try_acquire("4", now) > Duration::from_millis(900));
+
+        let clone = limiter.clone();
+        assert!(clone.try_acquire("5", now) > Duration::from_millis(900));
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn acquire_waits_for_a_token() {
+        let limiter = RateLimiter::builder()
+            .per_webhook(1, Duration::from_millis(100))
+            .no_global_limit()
+            .build();
+        let started = Instant::now();
+
+        limiter.acquire("1").await;
+        limiter.acquire("1").await;
+
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+}
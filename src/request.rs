@@ -0,0 +1,320 @@
+//! Building webhook requests without executing them.
+//!
+//! Useful for edge runtimes and HTTP stacks the client does not support: build the request
+//! with this crate's builders and validation, then perform the I/O yourself.
+//! Enabled by the `request` feature, which does not pull in any async runtime.
+//!
+//! The errors for a webhook URL Discord does not accept, `InvalidToken` and `NotFound`, are
+//! defined here as well, since every client returns them, and so is reading the rate limit
+//! headers of a response.
+
+use bytes::Bytes;
+use http::{header, HeaderMap, Method, Request, Response, StatusCode};
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::error::Error;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::models::{Attachment, Message};
+use crate::{WebhookError, WebhookResult};
+
+/// Validates `message` and serializes it into a request executing the webhook at `url`.
+///
+/// The body is JSON, or `multipart/form-data` if the message has attachments with data.
+///
+/// Example
+/// ```no_run
+/// # use webhook::models::Message;
+/// # struct HttpStack;
+/// # impl HttpStack {
+/// #     async fn execute(&self, request: http::Request<bytes::Bytes>) -> webhook::WebhookResult<()> { Ok(()) }
+/// # }
+/// # async fn run(url: String, my_http_stack: HttpStack) -> webhook::WebhookResult<()> {
+/// let mut message = Message::new();
+/// message.content("Deployed!");
+/// let request = webhook::request::build_request(&url, &message)?;
+/// let response = my_http_stack.execute(request).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn build_request(url: &str, message: &Message) -> WebhookResult<Request<Bytes>> {
+    validate(message)?;
+    let (content_type, body) = message_body(&serde_json::to_value(message)?, &message.attachments)?;
+    Ok(execute_request_builder(url)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Bytes::from(body))?)
+}
+
+/// Checks the message against the Discord API limits.
+/// The error is a `ValidationReport` listing every violation.
+pub(crate) fn validate(message: &Message) -> WebhookResult<()> {
+    Ok(message.validate()?)
+}
+
+/// A builder for a `POST` request to the webhook at `url`. The content type is left to
+/// the body, see `message_body`.
+pub(crate) fn execute_request_builder(url: &str) -> http::request::Builder {
+    Request::builder().method(Method::POST).uri(url)
+}
+
+/// Discord rejected the token of the webhook URL, with `401 Unauthorized` or `403 Forbidden`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidToken {
+    status: StatusCode,
+}
+
+impl InvalidToken {
+    /// The status Discord answered with.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl fmt::Display for InvalidToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the webhook token is invalid ({})", self.status)
+    }
+}
+
+impl Error for InvalidToken {}
+
+/// The webhook does not exist, e.g. because it was deleted; Discord answered with
+/// `404 Not Found`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotFound;
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the webhook does not exist")
+    }
+}
+
+impl Error for NotFound {}
+
+/// The typed error for a request to the webhook URL that failed with `status`, if the status
+/// means the URL is not usable.
+pub(crate) fn webhook_url_error(status: StatusCode) -> Option<Box<WebhookError>> {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(Box::new(InvalidToken { status })),
+        StatusCode::NOT_FOUND => Some(Box::new(NotFound)),
+        _ => None,
+    }
+}
+
+/// The number of seconds in the header `name`, e.g. `x-ratelimit-reset-after`.
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub(crate) fn header_seconds(headers: &HeaderMap, name: &str) -> Option<Duration> {
+    let seconds: f64 = headers.get(name)?.to_str().ok()?.parse().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// How long a `429 Too Many Requests` response asks to wait.
+#[cfg(any(feature = "client", feature = "async-io", feature = "wasm"))]
+pub(crate) fn retry_after(response: &Response<Vec<u8>>) -> Duration {
+    let from_body = serde_json::from_slice::<Value>(response.body())
+        .ok()
+        .and_then(|body| body["retry_after"].as_f64())
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+    from_body
+        .or_else(|| header_seconds(response.headers(), "retry-after"))
+        .unwrap_or(Duration::from_secs(1))
+}
+
+/// The `X-Audit-Log-Reason` header, recording why a webhook was changed in the audit log.
+pub(crate) const AUDIT_LOG_REASON: &str = "x-audit-log-reason";
+
+/// `reason` as the value of the `X-Audit-Log-Reason` header, which Discord expects
+/// percent-encoded so that reasons are not limited to ASCII.
+pub(crate) fn audit_log_reason_value(reason: &str) -> String {
+    let mut encoded = String::with_capacity(reason.len());
+    for byte in reason.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// The content type and body of a request sending `payload`.
+///
+/// The payload is sent as JSON unless one of the `attachments` has data to upload; then the
+/// body is `multipart/form-data` with the payload in the `payload_json` part and every file
+/// in a `files[<id>]` part.
+pub(crate) fn message_body(
+    payload: &Value,
+    attachments: &[Attachment],
+) -> WebhookResult<(String, Vec<u8>)> {
+    if attachments
+        .iter()
+        .any(|attachment| attachment.data.is_none() && attachment.source_url.is_some())
+    {
+        return Err("Attachments from URLs must be downloaded before the request is built".into());
+    }
+    let payload = serde_json::to_vec(payload)?;
+    if attachments
+        .iter()
+        .all(|attachment| attachment.data.is_none())
+    {
+        return Ok(("application/json".to_owned(), payload));
+    }
+
+    let multipart = Multipart::new();
+    let mut body = multipart.payload_part(&payload);
+    for attachment in attachments {
+        if let Some(data) = attachment.data.as_ref() {
+            body.extend_from_slice(&multipart.file_header(attachment));
+            body.extend_from_slice(data);
+        }
+    }
+    body.extend_from_slice(&multipart.end());
+
+    Ok((multipart.content_type(), body))
+}
+
+/// The JSON payload of a request body made by `message_body`: the body itself, or the
+/// `payload_json` part of a request with files.
+pub(crate) fn payload_json(body: &[u8]) -> Option<Value> {
+    if let Ok(json) = serde_json::from_slice(body) {
+        return Some(json);
+    }
+    let body = String::from_utf8_lossy(body);
+    let part = &body[body.find(r#"name="payload_json""#)?..];
+    let payload = &part[part.find("\r\n\r\n")? + 4..];
+    let payload = &payload[..payload.find("\r\n--")?];
+    serde_json::from_str(payload).ok()
+}
+
+/// The framing of a `multipart/form-data` body sending a message with files.
+///
+/// The body is the payload part, then a header and the data for every file, then the end.
+pub(crate) struct Multipart {
+    boundary: String,
+}
+
+impl Multipart {
+    /// Starts a body with a random boundary.
+    pub(crate) fn new() -> Self {
+        Self {
+            boundary: format!(
+                "webhook-rs-{:016x}",
+                RandomState::new().build_hasher().finish()
+            ),
+        }
+    }
+
+    pub(crate) fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// The `payload_json` part, which opens the body.
+    pub(crate) fn payload_part(&self, payload: &[u8]) -> Vec<u8> {
+        let mut part = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"payload_json\"\r\nContent-Type: application/json\r\n\r\n",
+            self.boundary
+        )
+        .into_bytes();
+        part.extend_from_slice(payload);
+        part
+    }
+
+    /// The start of the part uploading `attachment`, followed by the file data.
+    pub(crate) fn file_header(&self, attachment: &Attachment) -> Vec<u8> {
+        format!(
+            "\r\n--{}\r\nContent-Disposition: form-data; name=\"files[{}]\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+            self.boundary,
+            attachment.id,
+            escape_filename(&attachment.filename)
+        )
+        .into_bytes()
+    }
+
+    pub(crate) fn end(&self) -> Vec<u8> {
+        format!("\r\n--{}--\r\n", self.boundary).into_bytes()
+    }
+}
+
+/// Keeps a filename from ending its quoted header parameter or the header line.
+fn escape_filename(filename: &str) -> String {
+    filename
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_request, webhook_url_error, InvalidToken, NotFound};
+    use crate::models::Message;
+    use http::StatusCode;
+
+    #[test]
+    fn request_is_serialized() {
+        let mut message = Message::new();
+        message.content("hello");
+        let request = build_request("https://discord.com/api/webhooks/0/token", &message).unwrap();
+
+        assert_eq!(request.method(), "POST");
+        assert_eq!(request.headers()["content-type"], "application/json");
+        let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+        assert_eq!(body["content"], "hello");
+    }
+
+    #[test]
+    fn attachments_are_uploaded_as_multipart() {
+        let mut message = Message::new();
+        message.content("see attached").attachment(
+            "report \"q3\".txt",
+            b"numbers".to_vec(),
+            |file| file.description("The Q3 report").spoiler(),
+        );
+        let request = build_request("https://discord.com/api/webhooks/0/token", &message).unwrap();
+
+        let content_type = request.headers()["content-type"].to_str().unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = String::from_utf8(request.body().to_vec()).unwrap();
+        let parts: Vec<&str> = body.split(&format!("--{}", boundary)).collect();
+        assert_eq!(parts.len(), 4);
+        assert!(parts[1].contains(r#"name="payload_json""#));
+        let payload: serde_json::Value =
+            serde_json::from_str(parts[1].split("\r\n\r\n").nth(1).unwrap().trim_end()).unwrap();
+        assert_eq!(payload["attachments"][0]["id"], "0");
+        assert_eq!(
+            payload["attachments"][0]["filename"],
+            "SPOILER_report \"q3\".txt"
+        );
+        assert_eq!(payload["attachments"][0]["description"], "The Q3 report");
+        assert!(parts[2].contains(r#"name="files[0]"; filename="SPOILER_report %22q3%22.txt""#));
+        assert!(parts[2].ends_with("\r\n\r\nnumbers\r\n"));
+        assert_eq!(parts[3], "--\r\n");
+    }
+
+    #[test]
+    fn invalid_message_is_rejected() {
+        let mut message = Message::new();
+        message.action_row(|row| row);
+        assert!(build_request("https://discord.com/api/webhooks/0/token", &message).is_err());
+    }
+
+    #[test]
+    fn webhook_url_errors_are_typed() {
+        let error = webhook_url_error(StatusCode::UNAUTHORIZED).unwrap();
+        assert_eq!(
+            error.downcast_ref::<InvalidToken>().unwrap().status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert!(webhook_url_error(StatusCode::FORBIDDEN)
+            .unwrap()
+            .is::<InvalidToken>());
+        assert!(webhook_url_error(StatusCode::NOT_FOUND)
+            .unwrap()
+            .is::<NotFound>());
+        assert!(webhook_url_error(StatusCode::BAD_REQUEST).is_none());
+    }
+}
@@ -0,0 +1,380 @@
+use http::StatusCode;
+use std::collections::hash_map::RandomState;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::breaker::CircuitOpen;
+use crate::client::api_error;
+use crate::request::retry_after;
+use crate::transport::{sleep, HttpResponse};
+use crate::WebhookResult;
+
+/// When and how often a failed request is sent again.
+///
+/// The delay before the n-th retry is `initial * 2^(n - 1)`, capped at `max`. With jitter
+/// enabled a random part of up to half the delay is dropped, so clients that failed at the
+/// same moment do not retry in lockstep. Rate limited requests wait as long as Discord asks
+/// instead.
+///
+/// If a request was sent more than once and still failed, the error is a `RetryError`
+/// listing every attempt.
+///
+/// Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use webhook::prelude::*;
+/// # fn run() -> WebhookResult<()> {
+/// let mut policy = RetryPolicy::new();
+/// policy
+///     .max_attempts(5)
+///     .backoff(Duration::from_millis(250), Duration::from_secs(10))
+///     .retry_on(RetryOn { rate_limits: false, ..RetryOn::default() });
+/// let client = WebhookClient::builder("URL").retry_policy(&policy).build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retry_on: RetryOn,
+}
+
+/// The failures a `RetryPolicy` retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryOn {
+    /// `5xx` responses.
+    pub server_errors: bool,
+    /// `429 Too Many Requests` responses.
+    pub rate_limits: bool,
+    /// Requests that got no response, e.g. refused or reset connections and timeouts.
+    pub connection_errors: bool,
+}
+
+impl Default for RetryOn {
+    fn default() -> Self {
+        Self {
+            server_errors: true,
+            rate_limits: true,
+            connection_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Up to 3 attempts, backing off from 500ms up to 30s with jitter, retrying every
+    /// transient failure.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retry_on: RetryOn::default(),
+        }
+    }
+
+    /// Total number of times a request is sent, including the first one.
+    pub fn max_attempts(&mut self, max_attempts: u32) -> &mut Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry and the limit the doubling delays are capped at.
+    pub fn backoff(&mut self, initial: Duration, max: Duration) -> &mut Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    pub fn jitter(&mut self, jitter: bool) -> &mut Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn retry_on(&mut self, retry_on: RetryOn) -> &mut Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    /// Sends a request until it succeeds, fails in a way that is not retried or runs out
+    /// of attempts.
+    ///
+    /// A failure on the first attempt is returned as is: an unsuccessful response or the
+    /// transport error. After a retry, failures become a `RetryError`.
+    pub(crate) async fn execute<Send, Fut>(&self, mut send: Send) -> WebhookResult<HttpResponse>
+    where
+        Send: FnMut() -> Fut,
+        Fut: Future<Output = WebhookResult<HttpResponse>>,
+    {
+        let mut attempts = Vec::new();
+        loop {
+            let result = send().await;
+            let wait = match result.as_ref() {
+                Ok(response) if response.status().is_success() => return result,
+                Ok(response) => self.response_wait(response, attempts.len() as u32),
+                Err(err) if err.is::<CircuitOpen>() => None,
+                Err(_) if self.retry_on.connection_errors => {
+                    Some(self.backoff_for(attempts.len() as u32))
+                }
+                Err(_) => None,
+            }
+            .filter(|_| attempts.len() as u32 + 1 < self.max_attempts);
+
+            if wait.is_none() && attempts.is_empty() {
+                return result;
+            }
+            let (status, error) = match result {
+                Ok(response) => (Some(response.status()), api_error(response)),
+                Err(err) => (None, err),
+            };
+            #[cfg(feature = "tracing")]
+            if let Some(wait) = wait {
+                tracing::info!(
+                    attempt = attempts.len() + 1,
+                    status = ?status.map(|status| status.as_u16()),
+                    wait_ms = wait.as_millis() as u64,
+                    error = %error,
+                    "retrying webhook request"
+                );
+            }
+            attempts.push(Attempt {
+                status,
+                error: error.to_string(),
+                wait,
+            });
+            match wait {
+                Some(wait) => {
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::retried("client");
+                    sleep(wait).await
+                }
+                None => {
+                    return Err(Box::new(RetryError {
+                        attempts,
+                        last_error: error,
+                    }))
+                }
+            }
+        }
+    }
+
+    fn response_wait(&self, response: &HttpResponse, retries: u32) -> Option<Duration> {
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS && self.retry_on.rate_limits {
+            Some(retry_after(response))
+        } else if status.is_server_error() && self.retry_on.server_errors {
+            Some(self.backoff_for(retries))
+        } else {
+            None
+        }
+    }
+
+    fn backoff_for(&self, retries: u32) -> Duration {
+        let delay = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(retries))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter {
+            delay - delay.mul_f64(random_fraction() / 2.0)
+        } else {
+            delay
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A random number in `[0, 1)`, good enough to spread out retries.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// One failed attempt of a request.
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    /// The response status, `None` if no response was received.
+    pub status: Option<StatusCode>,
+    pub error: String,
+    /// How long the policy waited before the next attempt, `None` for the last attempt.
+    pub wait: Option<Duration>,
+}
+
+/// A request that failed even though it was retried.
+#[derive(Debug)]
+pub struct RetryError {
+    attempts: Vec<Attempt>,
+    last_error: Box<dyn Error + Send + Sync>,
+}
+
+impl RetryError {
+    /// Every attempt in the order they were made.
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+}
+
+impl fmt::Display for RetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "request failed after {} attempts: {}",
+            self.attempts.len(),
+            self.last_error
+        )
+    }
+}
+
+impl Error for RetryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.last_error)
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::{RetryError, RetryOn, RetryPolicy};
+    use crate::client::WebhookClient;
+    use crate::models::Message;
+    use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+    use crate::WebhookResult;
+    use futures_util::future::BoxFuture;
+    use http::StatusCode;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Answers with the scripted statuses in order, `0` standing for a connection error,
+    /// then with 204.
+    struct ScriptedTransport {
+        statuses: Mutex<Vec<u16>>,
+        requests: Arc<Mutex<usize>>,
+    }
+
+    impl WebhookTransport for ScriptedTransport {
+        fn execute(&self, _: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+            *self.requests.lock().unwrap() += 1;
+            let status = self.statuses.lock().unwrap().pop().unwrap_or(204);
+            Box::pin(async move {
+                if status == 0 {
+                    return Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset").into());
+                }
+                Ok(http::Response::builder()
+                    .status(status)
+                    .body(br#"{"retry_after": 0.01}"#.to_vec())?)
+            })
+        }
+    }
+
+    /// A client answering with `statuses` (in reverse) and the number of requests it sent.
+    fn scripted_client(
+        statuses: Vec<u16>,
+        policy: &RetryPolicy,
+    ) -> (WebhookClient, Arc<Mutex<usize>>) {
+        let requests = Arc::new(Mutex::new(0));
+        let transport = ScriptedTransport {
+            statuses: Mutex::new(statuses),
+            requests: requests.clone(),
+        };
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(transport)
+            .retry_policy(policy)
+            .build()
+            .unwrap();
+        (client, requests)
+    }
+
+    fn policy() -> RetryPolicy {
+        let mut policy = RetryPolicy::new();
+        policy.backoff(Duration::from_millis(1), Duration::from_millis(4));
+        policy
+    }
+
+    fn message() -> Message {
+        let mut message = Message::new();
+        message.content("content");
+        message
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried() {
+        let mut policy = policy();
+        policy.max_attempts(4);
+        let (client, requests) = scripted_client(vec![0, 429, 503], &policy);
+
+        assert!(client.send_message(&message()).await.unwrap());
+        assert_eq!(*requests.lock().unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_report_every_attempt() {
+        let (client, requests) = scripted_client(vec![500, 502, 503], &policy());
+
+        let err = client.send_message(&message()).await.unwrap_err();
+        let err = err.downcast::<RetryError>().unwrap();
+        let statuses: Vec<_> = err
+            .attempts()
+            .iter()
+            .map(|attempt| attempt.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Some(StatusCode::SERVICE_UNAVAILABLE),
+                Some(StatusCode::BAD_GATEWAY),
+                Some(StatusCode::INTERNAL_SERVER_ERROR)
+            ]
+        );
+        assert!(err.attempts()[2].wait.is_none());
+        assert_eq!(*requests.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn other_failures_are_not_retried() {
+        let mut policy = policy();
+        policy.retry_on(RetryOn {
+            rate_limits: false,
+            ..RetryOn::default()
+        });
+
+        let (client, requests) = scripted_client(vec![429], &policy);
+        let err = client.send_message(&message()).await.unwrap_err();
+        assert!(!err.is::<RetryError>());
+        assert_eq!(*requests.lock().unwrap(), 1);
+
+        let (client, requests) = scripted_client(vec![400], &policy);
+        assert!(client.send_message(&message()).await.is_err());
+        assert_eq!(*requests.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_limit() {
+        let mut policy = RetryPolicy::new();
+        policy
+            .backoff(Duration::from_secs(1), Duration::from_secs(5))
+            .jitter(false);
+        let delays: Vec<_> = (0..4).map(|retries| policy.backoff_for(retries)).collect();
+        assert_eq!(
+            delays,
+            vec![1, 2, 4, 5]
+                .into_iter()
+                .map(Duration::from_secs)
+                .collect::<Vec<_>>()
+        );
+
+        policy.jitter(true);
+        let delay = policy.backoff_for(1);
+        assert!(delay > Duration::from_secs(1) && delay <= Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::client::{WebhookClient, WebhookResult};
+use crate::models::{Color, Message};
+
+/// How severe the event a message reports is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Debug => "debug",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// Sends messages to a different webhook depending on their severity.
+///
+/// A message goes to the route of its severity or, if that has none, to the route of the
+/// closest lower severity. Each route can decorate its messages with a `RouteTemplate`.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # const LOGS_URL: &str = "https://discord.com/api/webhooks/0/logs";
+/// # const ALERTS_URL: &str = "https://discord.com/api/webhooks/1/alerts";
+/// # async fn run() -> WebhookResult<()> {
+/// let mut router = WebhookRouter::new();
+/// router
+///     .route(Severity::Info, WebhookClient::new(LOGS_URL), |template| template)
+///     .route(Severity::Error, WebhookClient::new(ALERTS_URL), |template| template
+///         .color(Color::RED)
+///         .prefix("**ERROR**")
+///         .mention("@here"));
+/// router.send(Severity::Error, |message| message.content("database is down")).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct WebhookRouter {
+    routes: BTreeMap<Severity, Route>,
+}
+
+struct Route {
+    client: WebhookClient,
+    template: RouteTemplate,
+}
+
+impl WebhookRouter {
+    pub fn new() -> Self {
+        Self {
+            routes: BTreeMap::new(),
+        }
+    }
+
+    /// Sends messages of `severity` with `client`, decorated by the template.
+    /// Replaces an earlier route for the same severity.
+    pub fn route<Func>(
+        &mut self,
+        severity: Severity,
+        client: WebhookClient,
+        function: Func,
+    ) -> &mut Self
+    where
+        Func: FnOnce(&mut RouteTemplate) -> &mut RouteTemplate,
+    {
+        let mut template = RouteTemplate::new();
+        function(&mut template);
+        self.routes.insert(severity, Route { client, template });
+        self
+    }
+
+    /// Builds the message, applies the route's template, validates it and sends it.
+    ///
+    /// Fails without sending if no route matches the severity.
+    pub async fn send<Func>(&self, severity: Severity, function: Func) -> WebhookResult<bool>
+    where
+        Func: FnOnce(&mut Message) -> &mut Message,
+    {
+        let mut message = Message::new();
+        function(&mut message);
+        self.send_message(severity, message).await
+    }
+
+    pub async fn send_message(
+        &self,
+        severity: Severity,
+        mut message: Message,
+    ) -> WebhookResult<bool> {
+        let route = self.route_for(severity).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("no webhook is routed for severity {}", severity),
+            )
+        })?;
+        route.template.apply(&mut message);
+        route.client.send_message(&message).await
+    }
+
+    fn route_for(&self, severity: Severity) -> Option<&Route> {
+        self.routes
+            .range(..=severity)
+            .next_back()
+            .map(|(_, route)| route)
+    }
+}
+
+/// Decorations a `WebhookRouter` route applies to every message it sends.
+#[derive(Debug, Clone, Default)]
+pub struct RouteTemplate {
+    color: Option<Color>,
+    prefix: Option<String>,
+    mention: Option<String>,
+}
+
+impl RouteTemplate {
+    pub fn new() -> Self {
+        Self {
+            color: None,
+            prefix: None,
+            mention: None,
+        }
+    }
+
+    /// Color of the embeds that do not set one themselves.
+    pub fn color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Text put in front of the content, separated by a space.
+    pub fn prefix(&mut self, prefix: &str) -> &mut Self {
+        self.prefix = Some(prefix.to_owned());
+        self
+    }
+
+    /// A mention put at the very start of the content, e.g. `@here` or
+    /// `fmt::role_mention(role_id)`.
+    pub fn mention(&mut self, mention: &str) -> &mut Self {
+        self.mention = Some(mention.to_owned());
+        self
+    }
+
+    fn apply(&self, message: &mut Message) {
+        if let Some(color) = self.color {
+            for embed in message
+                .embeds
+                .iter_mut()
+                .filter(|embed| embed.color.is_none())
+            {
+                embed.color = Some(color);
+            }
+        }
+
+        let parts: Vec<&str> = self
+            .mention
+            .iter()
+            .chain(self.prefix.iter())
+            .chain(message.content.iter())
+            .map(String::as_str)
+            .filter(|part| !part.is_empty())
+            .collect();
+        if !parts.is_empty() {
+            message.content = Some(parts.join(" "));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteTemplate, Severity};
+    use crate::models::{Color, Message};
+
+    #[test]
+    fn template_decorates_the_message() {
+        let mut template = RouteTemplate::new();
+        template
+            .color(Color::RED)
+            .prefix("**ERROR**")
+            .mention("@here");
+
+        let mut message = Message::new();
+        message
+            .content("database is down")
+            .embed(|embed| embed.title("untouched"))
+            .embed(|embed| embed.title("kept").color(Color::GREEN));
+        template.apply(&mut message);
+
+        assert_eq!(
+            message.content.as_deref(),
+            Some("@here **ERROR** database is down")
+        );
+        assert_eq!(message.embeds[0].color, Some(Color::RED));
+        assert_eq!(message.embeds[1].color, Some(Color::GREEN));
+
+        let mut message = Message::new();
+        RouteTemplate::new().apply(&mut message);
+        assert_eq!(message.content, None);
+    }
+
+    #[test]
+    fn severities_are_ordered() {
+        assert!(Severity::Debug < Severity::Info);
+        assert!(Severity::Error < Severity::Critical);
+        assert_eq!(Severity::Warning.to_string(), "warning");
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn messages_fall_back_to_lower_routes() {
+        use super::WebhookRouter;
+        use crate::client::WebhookClient;
+        use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+        use crate::WebhookResult;
+        use futures_util::future::BoxFuture;
+        use std::sync::{Arc, Mutex};
+
+        /// Records the content of every request under its route name.
+        struct Named(&'static str, Arc<Mutex<Vec<String>>>);
+
+        impl WebhookTransport for Named {
+            fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+                let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+                self.1.lock().unwrap().push(format!(
+                    "{}: {}",
+                    self.0,
+                    body["content"].as_str().unwrap()
+                ));
+                Box::pin(async { Ok(http::Response::builder().status(204).body(vec![])?) })
+            }
+        }
+
+        let sent = Arc::new(Mutex::new(vec![]));
+        let client = |name| {
+            WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+                .transport(Named(name, sent.clone()))
+                .build()
+                .unwrap()
+        };
+        let mut router = WebhookRouter::new();
+        router
+            .route(Severity::Info, client("logs"), |template| template)
+            .route(Severity::Error, client("alerts"), |template| {
+                template.prefix("[error]")
+            });
+
+        for severity in &[Severity::Info, Severity::Warning, Severity::Critical] {
+            router
+                .send(*severity, |message| message.content("event"))
+                .await
+                .unwrap();
+        }
+        assert!(router
+            .send(Severity::Debug, |message| message.content("event"))
+            .await
+            .is_err());
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec!["logs: event", "logs: event", "alerts: [error] event"]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+use crate::client::{WebhookClient, WebhookResult};
+use crate::models::Message;
+
+/// Sends messages at a later time from background tokio tasks.
+///
+/// Every scheduled send returns a `ScheduledSend` handle to cancel it or wait for its
+/// outcome; the sender itself keeps track of the sends that are still pending. Dropping a
+/// handle does not cancel the send. Scheduling must happen inside a tokio runtime.
+///
+/// Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use webhook::prelude::*;
+/// # use webhook::schedule::ScheduledSender;
+/// # const URL: &str = "https://discord.com/api/webhooks/0/token";
+/// # async fn run(meeting_cancelled: bool) -> WebhookResult<()> {
+/// let scheduler = ScheduledSender::new(WebhookClient::new(URL));
+/// let mut reminder = Message::new();
+/// reminder.content("Standup in 5 minutes");
+/// let send = scheduler.schedule_in(reminder, Duration::from_secs(25 * 60))?;
+/// if meeting_cancelled {
+///     send.cancel();
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ScheduledSender {
+    client: Arc<WebhookClient>,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<()>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl ScheduledSender {
+    pub fn new(client: WebhookClient) -> Self {
+        Self {
+            client: Arc::new(client),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Validates the message and sends it once `delay` has passed.
+    pub fn schedule_in(&self, message: Message, delay: Duration) -> WebhookResult<ScheduledSend> {
+        self.schedule_at(message, Instant::now() + delay)
+    }
+
+    /// Validates the message and sends it at `at`, or right away if `at` has passed.
+    pub fn schedule_at(&self, message: Message, at: Instant) -> WebhookResult<ScheduledSend> {
+        message.validate()?;
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (cancel_sender, cancelled) = oneshot::channel();
+        let (outcome_sender, outcome) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, cancel_sender);
+
+        let client = self.client.clone();
+        let pending = self.pending.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::time::sleep_until(at.into()) => {}
+                _ = cancelled => return,
+            }
+            // a send that is no longer pending was cancelled just now
+            if pending.lock().unwrap().remove(&id).is_none() {
+                return;
+            }
+            let _ = outcome_sender.send(client.send_message(&message).await);
+        });
+
+        Ok(ScheduledSend {
+            id,
+            pending: self.pending.clone(),
+            outcome,
+        })
+    }
+
+    /// The number of sends that are scheduled and neither sent nor cancelled.
+    pub fn pending(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// Cancels every pending send.
+    pub fn cancel_all(&self) {
+        for (_, cancel) in self.pending.lock().unwrap().drain() {
+            let _ = cancel.send(());
+        }
+    }
+}
+
+/// A handle to a send scheduled by a `ScheduledSender`.
+pub struct ScheduledSend {
+    id: u64,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<()>>>>,
+    outcome: oneshot::Receiver<WebhookResult<bool>>,
+}
+
+impl ScheduledSend {
+    /// Cancels the send. Returns `false` if it is too late because the message is already
+    /// being sent, or if it was cancelled before.
+    pub fn cancel(&self) -> bool {
+        match self.pending.lock().unwrap().remove(&self.id) {
+            Some(cancel) => {
+                let _ = cancel.send(());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Waits until the message is sent and returns the result of the send.
+    /// Fails if the send was cancelled.
+    pub async fn wait(self) -> WebhookResult<bool> {
+        self.outcome
+            .await
+            .map_err(|_| "The scheduled send was cancelled")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScheduledSender;
+    use crate::client::WebhookClient;
+    use crate::models::Message;
+    use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+    use crate::WebhookResult;
+    use futures_util::future::BoxFuture;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    struct RecordingTransport(Arc<Mutex<Vec<String>>>);
+
+    impl WebhookTransport for RecordingTransport {
+        fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+            let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+            self.0
+                .lock()
+                .unwrap()
+                .push(body["content"].as_str().unwrap().to_owned());
+            Box::pin(async { Ok(http::Response::builder().status(204).body(vec![])?) })
+        }
+    }
+
+    fn message(content: &str) -> Message {
+        let mut message = Message::new();
+        message.content(content);
+        message
+    }
+
+    #[tokio::test]
+    async fn cancelled_sends_are_not_delivered() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+            .transport(RecordingTransport(sent.clone()))
+            .build()
+            .unwrap();
+        let scheduler = ScheduledSender::new(client);
+
+        let kept = scheduler
+            .schedule_in(message("kept"), Duration::from_millis(20))
+            .unwrap();
+        let cancelled = scheduler
+            .schedule_in(message("cancelled"), Duration::from_millis(10))
+            .unwrap();
+        let later = scheduler
+            .schedule_in(message("later"), Duration::from_secs(60))
+            .unwrap();
+        assert!(scheduler
+            .schedule_in(message(&"a".repeat(2001)), Duration::from_millis(10))
+            .is_err());
+        assert_eq!(scheduler.pending(), 3);
+
+        assert!(cancelled.cancel());
+        assert!(!cancelled.cancel());
+        assert!(kept.wait().await.unwrap());
+        assert!(cancelled.wait().await.is_err());
+
+        scheduler.cancel_all();
+        assert_eq!(scheduler.pending(), 0);
+        assert!(later.wait().await.is_err());
+        assert_eq!(*sent.lock().unwrap(), vec!["kept"]);
+    }
+}
@@ -0,0 +1,199 @@
+//! Slack-format payloads, which Discord accepts at the `/slack` endpoint of a webhook.
+//!
+//! For tooling that already emits Slack messages; new code should prefer `Message`, which
+//! supports everything Discord offers. Discord maps the Slack attachments to embeds.
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::Color;
+
+/// A message in Slack's incoming webhook format, sent with `WebhookClient::send_slack`.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # use webhook::slack::SlackMessage;
+/// # async fn run(client: WebhookClient) -> WebhookResult<()> {
+/// let mut message = SlackMessage::new();
+/// message.text("Deploy finished").attachment(|attachment| {
+///     attachment
+///         .color(Color::GREEN)
+///         .title("billing 1.2.3")
+///         .field("Duration", "4m 12s", true)
+/// });
+/// client.send_slack(&message).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlackMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<SlackAttachment>,
+}
+
+impl SlackMessage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&mut self, text: impl Into<String>) -> &mut Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    pub fn username(&mut self, username: impl Into<String>) -> &mut Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    pub fn icon_url(&mut self, icon_url: impl Into<String>) -> &mut Self {
+        self.icon_url = Some(icon_url.into());
+        self
+    }
+
+    pub fn attachment<Func>(&mut self, func: Func) -> &mut Self
+    where
+        Func: FnOnce(&mut SlackAttachment) -> &mut SlackAttachment,
+    {
+        let mut attachment = SlackAttachment::new();
+        func(&mut attachment);
+        self.attachments.push(attachment);
+        self
+    }
+}
+
+/// A Slack message attachment, shown as an embed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlackAttachment {
+    /// `#rrggbb`, or one of Slack's `good`, `warning` and `danger`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pretext: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<SlackField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_icon: Option<String>,
+    /// Unix time in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ts: Option<u64>,
+}
+
+impl SlackAttachment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.color = Some(color.into().to_string());
+        self
+    }
+
+    pub fn pretext(&mut self, pretext: impl Into<String>) -> &mut Self {
+        self.pretext = Some(pretext.into());
+        self
+    }
+
+    pub fn author(
+        &mut self,
+        name: impl Into<String>,
+        link: impl Into<Option<String>>,
+        icon: impl Into<Option<String>>,
+    ) -> &mut Self {
+        self.author_name = Some(name.into());
+        self.author_link = link.into();
+        self.author_icon = icon.into();
+        self
+    }
+
+    pub fn title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn title_link(&mut self, title_link: impl Into<String>) -> &mut Self {
+        self.title_link = Some(title_link.into());
+        self
+    }
+
+    pub fn text(&mut self, text: impl Into<String>) -> &mut Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Adds a field; `short` fields are shown side by side.
+    pub fn field(
+        &mut self,
+        title: impl Into<String>,
+        value: impl Into<String>,
+        short: bool,
+    ) -> &mut Self {
+        self.fields.push(SlackField {
+            title: title.into(),
+            value: value.into(),
+            short,
+        });
+        self
+    }
+
+    pub fn image_url(&mut self, image_url: impl Into<String>) -> &mut Self {
+        self.image_url = Some(image_url.into());
+        self
+    }
+
+    pub fn thumb_url(&mut self, thumb_url: impl Into<String>) -> &mut Self {
+        self.thumb_url = Some(thumb_url.into());
+        self
+    }
+
+    pub fn footer(
+        &mut self,
+        text: impl Into<String>,
+        icon: impl Into<Option<String>>,
+    ) -> &mut Self {
+        self.footer = Some(text.into());
+        self.footer_icon = icon.into();
+        self
+    }
+
+    pub fn timestamp(&mut self, time: SystemTime) -> &mut Self {
+        self.ts = time
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|since| since.as_secs());
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SlackField {
+    pub title: String,
+    pub value: String,
+    #[serde(default)]
+    pub short: bool,
+}
@@ -0,0 +1,66 @@
+//! Delivery metrics, recorded through the `metrics` facade when the `metrics` feature is
+//! enabled. Install any `metrics` recorder, e.g. a Prometheus exporter, to collect them.
+//!
+//! Messages are counted by the subsystem that sent them, in the `source` label: `client` for
+//! the sends of `WebhookClient` (`send_message` and the sends built on it, `send_message_for_id`,
+//! `send_prepared`, `send_streaming`, `send_json`, `send_slack` and `send_github`), `queue` for
+//! `WebhookQueue`.
+//!
+//! Example
+//! ```no_run
+//! # struct PrometheusBuilder;
+//! # impl PrometheusBuilder {
+//! #     fn new() -> Self { PrometheusBuilder }
+//! #     fn install(self) -> webhook::WebhookResult<()> { Ok(()) }
+//! # }
+//! # fn main() -> webhook::WebhookResult<()> {
+//! PrometheusBuilder::new().install()?;
+//! webhook::telemetry::describe();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Instant;
+
+/// Counter of messages Discord accepted.
+pub const MESSAGES_SENT: &str = "webhook_messages_sent_total";
+/// Counter of messages that could not be delivered, after any retries.
+pub const MESSAGES_FAILED: &str = "webhook_messages_failed_total";
+/// Counter of `429 Too Many Requests` responses.
+pub const RATE_LIMITED: &str = "webhook_rate_limited_total";
+/// Counter of requests sent again after a failure.
+pub const RETRIES: &str = "webhook_retries_total";
+/// Histogram of the time a message took to be delivered or to fail, retries included, in
+/// seconds.
+pub const SEND_DURATION: &str = "webhook_send_duration_seconds";
+
+/// Registers the descriptions of the metrics with the installed recorder.
+pub fn describe() {
+    metrics::describe_counter!(MESSAGES_SENT, "Messages Discord accepted");
+    metrics::describe_counter!(
+        MESSAGES_FAILED,
+        "Messages that could not be delivered, after any retries"
+    );
+    metrics::describe_counter!(RATE_LIMITED, "Requests Discord answered with 429");
+    metrics::describe_counter!(RETRIES, "Requests sent again after a failure");
+    metrics::describe_histogram!(
+        SEND_DURATION,
+        metrics::Unit::Seconds,
+        "Time until a message was delivered or failed, retries included"
+    );
+}
+
+/// Records the outcome of sending a message that was started at `started`.
+pub(crate) fn delivered(source: &'static str, sent: bool, started: Instant) {
+    let name = if sent { MESSAGES_SENT } else { MESSAGES_FAILED };
+    metrics::counter!(name, "source" => source).increment(1);
+    metrics::histogram!(SEND_DURATION, "source" => source).record(started.elapsed().as_secs_f64());
+}
+
+pub(crate) fn rate_limited() {
+    metrics::counter!(RATE_LIMITED).increment(1);
+}
+
+pub(crate) fn retried(source: &'static str) {
+    metrics::counter!(RETRIES, "source" => source).increment(1);
+}
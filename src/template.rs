@@ -0,0 +1,181 @@
+//! Messages with `{{placeholder}}` markers that are filled in before sending.
+
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::models::Message;
+use crate::WebhookResult;
+
+/// A message definition whose strings (content, embeds, buttons, ...) contain `{{name}}`
+/// placeholders.
+///
+/// Templates are loaded from JSON in Discord's payload format or built with the usual
+/// builders. `render` replaces every placeholder with its value and returns a validated
+/// message. Whitespace inside the braces is ignored, so `{{ name }}` works as well.
+///
+/// Example
+/// ```no_run
+/// # use std::collections::HashMap;
+/// # use webhook::prelude::*;
+/// # use webhook::template::MessageTemplate;
+/// # async fn run(client: WebhookClient) -> WebhookResult<()> {
+/// let template = MessageTemplate::from_json(r#"{
+///     "content": "Deploy of {{service}} finished",
+///     "embeds": [{"title": "{{service}} {{version}}", "color": 5763719}]
+/// }"#)?;
+///
+/// let mut values = HashMap::new();
+/// values.insert("service", "billing".to_owned());
+/// values.insert("version", "1.2.3".to_owned());
+/// client.send_message(&template.render(&values)?).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageTemplate {
+    template: Value,
+}
+
+impl MessageTemplate {
+    /// Loads a template from a JSON message payload.
+    pub fn from_json(json: &str) -> WebhookResult<Self> {
+        let message: Message = serde_json::from_str(json)?;
+        Self::from_message(&message)
+    }
+
+    /// Uses a message built with placeholders in its strings as template.
+    pub fn from_message(message: &Message) -> WebhookResult<Self> {
+        Ok(Self {
+            template: serde_json::to_value(message)?,
+        })
+    }
+
+    /// The names of all placeholders in the template, sorted and without duplicates.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        visit_strings(&self.template, &mut |text| {
+            let mut rest = text;
+            while let Some((name, after)) = next_placeholder(rest) {
+                names.insert(name.to_owned());
+                rest = after;
+            }
+        });
+        names.into_iter().collect()
+    }
+
+    /// Fills in the placeholders and validates the resulting message.
+    ///
+    /// Fails if a placeholder has no value or the message exceeds Discord's limits once the
+    /// values are filled in.
+    pub fn render(&self, values: &HashMap<&str, String>) -> WebhookResult<Message> {
+        let missing: Vec<String> = self
+            .placeholders()
+            .into_iter()
+            .filter(|name| !values.contains_key(name.as_str()))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("No value for the placeholders {}", missing.join(", ")).into());
+        }
+
+        let mut rendered = self.template.clone();
+        substitute(&mut rendered, values);
+        let message: Message = serde_json::from_value(rendered)?;
+        message.validate()?;
+        Ok(message)
+    }
+}
+
+/// Splits off the first `{{name}}` of `text`, returning the trimmed name and the rest after it.
+fn next_placeholder(text: &str) -> Option<(&str, &str)> {
+    let start = text.find("{{")?;
+    let end = text[start + 2..].find("}}")? + start + 2;
+    Some((text[start + 2..end].trim(), &text[end + 2..]))
+}
+
+fn visit_strings(value: &Value, visit: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(text) => visit(text),
+        Value::Array(values) => values.iter().for_each(|value| visit_strings(value, visit)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|value| visit_strings(value, visit)),
+        _ => {}
+    }
+}
+
+fn substitute(value: &mut Value, values: &HashMap<&str, String>) {
+    match value {
+        Value::String(text) => {
+            let mut rendered = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some((name, after)) = next_placeholder(rest) {
+                rendered.push_str(&rest[..rest.find("{{").unwrap()]);
+                rendered.push_str(&values[name]);
+                rest = after;
+            }
+            rendered.push_str(rest);
+            *text = rendered;
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| substitute(item, values)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| substitute(field, values)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MessageTemplate;
+    use crate::models::Message;
+    use std::collections::HashMap;
+
+    const TEMPLATE: &str = r#"{
+        "content": "Deploy of {{service}} finished",
+        "embeds": [{"title": "{{ service }} {{version}}", "fields": [{"name": "By", "value": "{{user}}"}]}],
+        "components": [{"type": 1, "components": [
+            {"type": 2, "style": 5, "label": "Changelog {{version}}", "url": "https://example.com"}
+        ]}]
+    }"#;
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        let template = MessageTemplate::from_json(TEMPLATE).unwrap();
+        assert_eq!(template.placeholders(), vec!["service", "user", "version"]);
+
+        let mut values = HashMap::new();
+        values.insert("service", "billing".to_owned());
+        values.insert("version", "1.2.3".to_owned());
+        values.insert("user", "{{service}}".to_owned());
+        let message = template.render(&values).unwrap();
+
+        assert_eq!(
+            message.content.as_deref(),
+            Some("Deploy of billing finished")
+        );
+        assert_eq!(message.embeds[0].title.as_deref(), Some("billing 1.2.3"));
+        // values are inserted as they are, not rendered again
+        assert_eq!(message.embeds[0].fields[0].value, "{{service}}");
+        let sent = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            sent["components"][0]["components"][0]["label"],
+            "Changelog 1.2.3"
+        );
+    }
+
+    #[test]
+    fn missing_values_and_invalid_results_are_rejected() {
+        let template = MessageTemplate::from_json(TEMPLATE).unwrap();
+        let mut values = HashMap::new();
+        values.insert("service", "billing".to_owned());
+        let error = template.render(&values).unwrap_err().to_string();
+        assert!(error.contains("user, version"), "{}", error);
+
+        let mut message = Message::new();
+        message.content("{{text}}");
+        let template = MessageTemplate::from_message(&message).unwrap();
+        let mut values = HashMap::new();
+        values.insert("text", "a".repeat(2001));
+        assert!(template.render(&values).is_err());
+    }
+}
@@ -0,0 +1,468 @@
+//! Test doubles for code that sends through a `WebhookClient`, so it can be unit tested
+//! without a Discord webhook or an HTTP server.
+//!
+//! `MockTransport` records every request instead of sending it and answers with scripted
+//! responses, `204 No Content` by default. For tests against recorded Discord responses, a
+//! `Recorder` middleware writes the requests of a real client and the responses to a file,
+//! which a `ReplayTransport` serves back later.
+//!
+//! Example
+//! ```no_run
+//! # use std::time::Duration;
+//! # use webhook::prelude::*;
+//! # use webhook::testing::MockTransport;
+//! # struct Job;
+//! # async fn alert_on_failure(client: &WebhookClient, job: &Job) -> WebhookResult<()> { Ok(()) }
+//! # async fn run(job: Job) -> WebhookResult<()> {
+//! let mock = MockTransport::new();
+//! mock.respond_rate_limited(Duration::from_millis(10));
+//! let client = mock.client();
+//!
+//! alert_on_failure(&client, &job).await?;
+//!
+//! let sent = mock.messages();
+//! assert_eq!(sent[0].embeds[0].title.as_deref(), Some("Job failed"));
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::future::BoxFuture;
+use http::{HeaderMap, Method, StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::client::{WebhookClient, WebhookClientBuilder};
+use crate::middleware::{Middleware, Next};
+use crate::models::Message;
+use crate::request::payload_json;
+use crate::transport::{HttpRequest, HttpResponse, WebhookTransport};
+use crate::url::redact_token;
+use crate::WebhookResult;
+
+/// A `WebhookTransport` that records requests and answers with scripted responses.
+///
+/// Clones share the recorded requests and the scripted responses, so a clone can be handed to
+/// the client and the original inspected afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    requests: Vec<RecordedRequest>,
+    responses: VecDeque<Scripted>,
+}
+
+#[derive(Debug)]
+enum Scripted {
+    Response(StatusCode, Vec<(&'static str, String)>, Vec<u8>),
+    Error(String),
+    Stall,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A client sending through this transport.
+    pub fn client(&self) -> WebhookClient {
+        self.builder()
+            .build()
+            .expect("a client with a custom transport always builds")
+    }
+
+    /// A builder of a client sending through this transport, for clients with further
+    /// settings like a retry policy.
+    pub fn builder(&self) -> WebhookClientBuilder {
+        let mut builder = WebhookClient::builder(Self::URL);
+        builder.transport(self.clone());
+        builder
+    }
+
+    /// Answers the next unanswered request with `status` and `body`. Responses are used in the
+    /// order they are scripted.
+    pub fn respond(&self, status: u16, body: impl Into<Vec<u8>>) -> &Self {
+        let status = StatusCode::from_u16(status).expect("a valid HTTP status code");
+        self.script(Scripted::Response(status, vec![], body.into()))
+    }
+
+    /// Answers the next unanswered request with `429 Too Many Requests`, asking to retry after
+    /// `retry_after`, like Discord does.
+    pub fn respond_rate_limited(&self, retry_after: Duration) -> &Self {
+        let seconds = retry_after.as_secs_f64();
+        let body = format!(
+            r#"{{"message": "You are being rate limited.", "retry_after": {}, "global": false}}"#,
+            seconds
+        );
+        self.script(Scripted::Response(
+            StatusCode::TOO_MANY_REQUESTS,
+            vec![("retry-after", seconds.to_string())],
+            body.into_bytes(),
+        ))
+    }
+
+    /// Fails the next unanswered request without a response, like a refused connection.
+    pub fn fail(&self, error: impl Into<String>) -> &Self {
+        self.script(Scripted::Error(error.into()))
+    }
+
+    /// Never answers the next unanswered request, like a server that stopped responding, e.g.
+    /// to test cancellations.
+    pub fn stall(&self) -> &Self {
+        self.script(Scripted::Stall)
+    }
+
+    fn script(&self, scripted: Scripted) -> &Self {
+        self.state.lock().unwrap().responses.push_back(scripted);
+        self
+    }
+
+    /// The requests received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The messages sent so far, read back from the JSON payloads of the requests. Requests
+    /// without a message payload, e.g. `get_information`, are left out.
+    pub fn messages(&self) -> Vec<Message> {
+        self.requests()
+            .iter()
+            .filter_map(RecordedRequest::json)
+            .filter_map(|payload| Message::from_value(payload).ok())
+            .collect()
+    }
+
+    /// Forgets the recorded requests and the responses not used yet.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.requests.clear();
+        state.responses.clear();
+    }
+
+    const URL: &'static str = "https://discord.com/api/webhooks/0/mock-token";
+}
+
+impl WebhookTransport for MockTransport {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        let (parts, body) = request.into_parts();
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(RecordedRequest {
+            method: parts.method,
+            uri: parts.uri,
+            headers: parts.headers,
+            body,
+        });
+        let scripted = state.responses.pop_front();
+        Box::pin(async move {
+            match scripted {
+                None => Ok(http::Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(vec![])?),
+                Some(Scripted::Response(status, headers, body)) => {
+                    let mut builder = http::Response::builder().status(status);
+                    for (name, value) in headers {
+                        builder = builder.header(name, value);
+                    }
+                    Ok(builder.body(body)?)
+                }
+                Some(Scripted::Error(error)) => Err(error.into()),
+                Some(Scripted::Stall) => futures_util::future::pending().await,
+            }
+        })
+    }
+}
+
+/// A request received by a `MockTransport`.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// The JSON payload of the request: the body, or the `payload_json` part of a request
+    /// with files.
+    pub fn json(&self) -> Option<serde_json::Value> {
+        payload_json(&self.body)
+    }
+}
+
+/// A request and its outcome, one JSON object per line in a recording.
+#[derive(Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    /// With the token replaced by `***`.
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payload: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    body: String,
+    /// The transport error of a request that got no response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A middleware writing every request of a client and its response to a file, to be served
+/// back by a `ReplayTransport`.
+///
+/// Tokens are removed from the recorded URLs and request headers are not recorded, so
+/// recordings can be committed. Of the request body only the JSON payload is kept.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # use webhook::testing::{Recorder, ReplayTransport};
+/// # fn run(url: String) -> WebhookResult<()> {
+/// // record once against a real webhook ...
+/// let client = WebhookClient::builder(&url)
+///     .middleware(Recorder::create("tests/recordings/alerts.jsonl")?)
+///     .build()?;
+/// // ... and replay in CI
+/// let client = WebhookClient::builder("https://discord.com/api/webhooks/0/token")
+///     .transport(ReplayTransport::open("tests/recordings/alerts.jsonl")?)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Recorder {
+    file: Mutex<File>,
+}
+
+impl Recorder {
+    /// Records to `path`, replacing an earlier recording.
+    pub fn create<P: AsRef<Path>>(path: P) -> WebhookResult<Self> {
+        Ok(Self {
+            file: Mutex::new(File::create(path)?),
+        })
+    }
+
+    fn record(&self, interaction: &Interaction) -> WebhookResult<()> {
+        let mut line = serde_json::to_vec(interaction)?;
+        line.push(b'\n');
+        self.file.lock().unwrap().write_all(&line)?;
+        Ok(())
+    }
+}
+
+impl Middleware for Recorder {
+    fn handle<'a>(
+        &'a self,
+        request: HttpRequest,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, WebhookResult<HttpResponse>> {
+        let mut interaction = Interaction {
+            method: request.method().to_string(),
+            url: redact_token(&request.uri().to_string()),
+            payload: payload_json(request.body()),
+            status: None,
+            headers: BTreeMap::new(),
+            body: String::new(),
+            error: None,
+        };
+        Box::pin(async move {
+            let result = next.run(request).await;
+            match result.as_ref() {
+                Ok(response) => {
+                    interaction.status = Some(response.status().as_u16());
+                    interaction.headers = response
+                        .headers()
+                        .iter()
+                        .filter_map(|(name, value)| {
+                            Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                        })
+                        .collect();
+                    interaction.body = String::from_utf8_lossy(response.body()).into_owned();
+                }
+                Err(err) => interaction.error = Some(err.to_string()),
+            }
+            self.record(&interaction)?;
+            result
+        })
+    }
+}
+
+/// A `WebhookTransport` answering with the responses of a recording made by a `Recorder`,
+/// in the recorded order.
+///
+/// A request that differs in method or URL (tokens aside) from the recorded one, or that
+/// comes after the recording ran out, fails the send.
+pub struct ReplayTransport {
+    interactions: Mutex<VecDeque<Interaction>>,
+}
+
+impl ReplayTransport {
+    pub fn open<P: AsRef<Path>>(path: P) -> WebhookResult<Self> {
+        let mut interactions = VecDeque::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                interactions.push_back(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self {
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    /// How many recorded responses have not been served yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions.lock().unwrap().len()
+    }
+
+    fn replay(&self, request: &HttpRequest) -> WebhookResult<HttpResponse> {
+        let method = request.method().to_string();
+        let url = redact_token(&request.uri().to_string());
+        let interaction = self
+            .interactions
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| format!("The recording has no response left for {} {}", method, url))?;
+        if interaction.method != method || interaction.url != url {
+            return Err(format!(
+                "Expected {} {} as in the recording, but got {} {}",
+                interaction.method, interaction.url, method, url
+            )
+            .into());
+        }
+        if let Some(error) = interaction.error {
+            return Err(error.into());
+        }
+
+        let mut builder = http::Response::builder().status(interaction.status.unwrap_or(204));
+        for (name, value) in interaction.headers.iter() {
+            builder = builder.header(name, value);
+        }
+        Ok(builder.body(interaction.body.into_bytes())?)
+    }
+}
+
+impl WebhookTransport for ReplayTransport {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        let response = self.replay(&request);
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockTransport, Recorder, ReplayTransport};
+    use crate::client::WebhookClient;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn requests_are_recorded_and_answered_as_scripted() {
+        let mock = MockTransport::new();
+        mock.respond(400, r#"{"message": "Invalid Form Body", "code": 50035}"#)
+            .respond_rate_limited(Duration::from_millis(1500))
+            .fail("connection refused");
+        let client = mock.client();
+
+        let error = client
+            .send(|message| message.content("first"))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Invalid Form Body"));
+        assert!(client
+            .send(|message| message.content("second"))
+            .await
+            .is_err());
+        let error = client
+            .send(|message| message.content("third"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "connection refused");
+        assert!(client
+            .send(|message| {
+                message
+                    .content("fourth")
+                    .attachment("log.txt", b"line".to_vec(), |file| file)
+            })
+            .await
+            .unwrap());
+
+        let contents: Vec<String> = mock
+            .messages()
+            .into_iter()
+            .filter_map(|message| message.content)
+            .collect();
+        assert_eq!(contents, ["first", "second", "third", "fourth"]);
+        let requests = mock.requests();
+        assert_eq!(requests[0].json().unwrap()["content"], "first");
+        assert_eq!(
+            requests[3].json().unwrap()["attachments"][0]["filename"],
+            "log.txt"
+        );
+        assert_eq!(requests[0].uri.path(), "/api/webhooks/0/mock-token");
+
+        mock.reset();
+        assert!(mock.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn recordings_are_replayed() {
+        let path = std::env::temp_dir().join(format!("webhook-recording-{}", std::process::id()));
+        let mock = MockTransport::new();
+        mock.respond(200, r#"{"id": "7"}"#).fail("connection reset");
+        let recording = WebhookClient::builder("https://discord.com/api/webhooks/1/secret")
+            .transport(mock.clone())
+            .middleware(Recorder::create(&path).unwrap())
+            .build()
+            .unwrap();
+        let id = recording
+            .send_message_for_id(crate::models::Message::new().content("deployed"))
+            .await
+            .unwrap();
+        assert!(recording
+            .send(|message| message.content("again"))
+            .await
+            .is_err());
+        assert!(recording
+            .send(|message| message.content("last"))
+            .await
+            .unwrap());
+
+        let recorded = std::fs::read_to_string(&path).unwrap();
+        assert!(!recorded.contains("secret"));
+        assert!(recorded.contains(r#""content":"deployed""#));
+
+        let replay = ReplayTransport::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let replaying = WebhookClient::builder("https://discord.com/api/webhooks/1/other")
+            .transport(replay)
+            .build()
+            .unwrap();
+        let replayed = replaying
+            .send_message_for_id(crate::models::Message::new().content("deployed"))
+            .await
+            .unwrap();
+        assert_eq!(replayed, id);
+        let error = replaying
+            .send(|message| message.content("again"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "connection reset");
+        assert!(replaying
+            .send(|message| message.content("last"))
+            .await
+            .unwrap());
+        let error = replaying
+            .send(|message| message.content("extra"))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("no response left"));
+    }
+}
@@ -0,0 +1,129 @@
+use async_io::Timer;
+use async_net::TcpStream;
+use futures_lite::{AsyncReadExt, FutureExt};
+use futures_util::future::BoxFuture;
+use http_types::Url;
+use std::time::Duration;
+
+use crate::transport::{
+    content_length, BodyTooLarge, HttpRequest, HttpResponse, Timeout, TransportConfig,
+    WebhookTransport,
+};
+use crate::WebhookResult;
+
+/// Runtime-agnostic transport speaking HTTP/1.1 through async-h1.
+///
+/// Sockets and timers come from async-io, the reactor shared by async-std and smol, so the
+/// transport runs on either executor without starting a second runtime.
+/// A new connection is opened for every request, so the pool settings have no effect.
+pub(crate) struct AsyncIoTransport {
+    config: TransportConfig,
+    tls: async_native_tls::TlsConnector,
+}
+
+impl AsyncIoTransport {
+    pub(crate) fn new(config: &TransportConfig) -> WebhookResult<Self> {
+        if config.proxy.is_some() {
+            return Err("Proxies are not supported by the async-io transport".into());
+        }
+        let mut tls = async_native_tls::TlsConnector::new();
+        for certificate in config.parse_root_certificates()? {
+            tls = tls.add_root_certificate(certificate);
+        }
+        Ok(AsyncIoTransport {
+            config: config.clone(),
+            tls: tls.danger_accept_invalid_certs(config.accept_invalid_certs),
+        })
+    }
+
+    /// Executes the request, reading at most `max_body_size` bytes of the response body.
+    async fn execute_with_timeout(
+        &self,
+        request: HttpRequest,
+        max_body_size: Option<usize>,
+    ) -> WebhookResult<HttpResponse> {
+        match self.config.timeout {
+            None => self.execute_inner(request, max_body_size).await,
+            Some(timeout) => {
+                let expired = async {
+                    Timer::after(timeout).await;
+                    Err(Timeout { after: timeout }.into())
+                };
+                self.execute_inner(request, max_body_size).or(expired).await
+            }
+        }
+    }
+
+    async fn execute_inner(
+        &self,
+        request: HttpRequest,
+        max_body_size: Option<usize>,
+    ) -> WebhookResult<HttpResponse> {
+        let (parts, body) = request.into_parts();
+        let url = Url::parse(&parts.uri.to_string())?;
+        let host = url.host_str().ok_or("Request URL has no host")?.to_owned();
+        let port = url.port_or_known_default().unwrap_or(443);
+
+        let mut outgoing = http_types::Request::new(parts.method.as_str().parse()?, url.clone());
+        for (name, value) in parts.headers.iter() {
+            outgoing.append_header(name.as_str(), value.to_str()?);
+        }
+        outgoing.set_body(body);
+
+        let stream = TcpStream::connect((host.as_str(), port)).await?;
+        let mut incoming = if url.scheme() == "https" {
+            let stream = self.tls.connect(&host, stream).await?;
+            async_h1::connect(stream, outgoing).await?
+        } else {
+            async_h1::connect(stream, outgoing).await?
+        };
+
+        let mut response = http::Response::builder().status(u16::from(incoming.status()));
+        for (name, values) in incoming.iter() {
+            for value in values.iter() {
+                response = response.header(name.as_str(), value.as_str());
+            }
+        }
+        let body = match max_body_size {
+            None => incoming.body_bytes().await?,
+            Some(limit) => {
+                let headers = response.headers_ref().ok_or("Invalid response header")?;
+                if content_length(headers).is_some_and(|length| length > limit) {
+                    return Err(BodyTooLarge::new(limit).into());
+                }
+                // one byte more than allowed tells a body at the limit from a larger one
+                let mut body = Vec::new();
+                incoming
+                    .take_body()
+                    .take(limit as u64 + 1)
+                    .read_to_end(&mut body)
+                    .await?;
+                if body.len() > limit {
+                    return Err(BodyTooLarge::new(limit).into());
+                }
+                body
+            }
+        };
+
+        Ok(response.body(body)?)
+    }
+}
+
+/// Waits on the async-io timer.
+pub(crate) async fn sleep(duration: Duration) {
+    Timer::after(duration).await;
+}
+
+impl WebhookTransport for AsyncIoTransport {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        Box::pin(self.execute_with_timeout(request, None))
+    }
+
+    fn execute_limited(
+        &self,
+        request: HttpRequest,
+        max_body_size: usize,
+    ) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        Box::pin(self.execute_with_timeout(request, Some(max_body_size)))
+    }
+}
@@ -0,0 +1,253 @@
+//! The HTTP layer of the client.
+//!
+//! Everything that depends on a specific async runtime lives in this module. The rest of the
+//! crate only builds `http` requests and interprets `http` responses, so adding a backend
+//! means implementing `WebhookTransport` and nothing else.
+//!
+//! The crate ships a hyper backend (feature `client`), a runtime-agnostic async-io backend
+//! (features `async-std` and `smol`, both enabling `async-io`) and a `fetch` backend for
+//! `wasm32-unknown-unknown` (feature `wasm`). To use another HTTP stack, implement
+//! `WebhookTransport` and hand it to `WebhookClientBuilder::transport`.
+
+#[cfg(feature = "client")]
+mod hyper_backend;
+#[cfg(feature = "socks")]
+mod socks;
+#[cfg(feature = "client")]
+pub(crate) use self::hyper_backend::{sleep, HyperTransport as DefaultTransport};
+
+#[cfg(all(feature = "async-io", not(feature = "client")))]
+mod async_io_backend;
+#[cfg(all(feature = "async-io", not(feature = "client")))]
+pub(crate) use self::async_io_backend::{sleep, AsyncIoTransport as DefaultTransport};
+
+#[cfg(all(feature = "wasm", not(any(feature = "client", feature = "async-io"))))]
+mod wasm_backend;
+#[cfg(all(feature = "wasm", not(any(feature = "client", feature = "async-io"))))]
+pub(crate) use self::wasm_backend::WasmTransport as DefaultTransport;
+
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use futures_util::stream::{BoxStream, TryStreamExt};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::WebhookResult;
+
+pub type HttpRequest = http::Request<Vec<u8>>;
+pub type HttpResponse = http::Response<Vec<u8>>;
+/// A request body produced while the request is sent, e.g. a file read in chunks.
+pub type HttpBodyStream = BoxStream<'static, std::io::Result<Bytes>>;
+
+/// Executes the HTTP requests of a `WebhookClient`.
+///
+/// Implementations receive fully built requests (method, URL, headers and body) and return the
+/// response with its body read completely. Non-success status codes are not errors at this level,
+/// the client interprets them.
+///
+/// Example
+/// ```no_run
+/// # use futures_util::future::BoxFuture;
+/// # use std::convert::TryInto;
+/// # use webhook::prelude::*;
+/// # use webhook::transport::{HttpRequest, HttpResponse, WebhookTransport};
+/// # mod reqwest {
+/// #     pub struct Client;
+/// #     pub struct Response;
+/// #     impl Client {
+/// #         pub async fn execute(&self, _: http::Request<Vec<u8>>) -> webhook::WebhookResult<Response> {
+/// #             Ok(Response)
+/// #         }
+/// #     }
+/// #     impl Response {
+/// #         pub fn status(&self) -> u16 { 204 }
+/// #         pub async fn bytes(self) -> webhook::WebhookResult<Vec<u8>> { Ok(vec![]) }
+/// #     }
+/// # }
+/// struct ReqwestTransport(reqwest::Client);
+///
+/// impl WebhookTransport for ReqwestTransport {
+///     fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+///         Box::pin(async move {
+///             let response = self.0.execute(request.try_into()?).await?;
+///             let mut builder = http::Response::builder().status(response.status());
+///             // copy headers ...
+///             Ok(builder.body(response.bytes().await?.to_vec())?)
+///         })
+///     }
+/// }
+///
+/// # fn run(url: &str, my_client: reqwest::Client) -> WebhookResult<()> {
+/// let client = WebhookClient::builder(url)
+///     .transport(ReqwestTransport(my_client))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait WebhookTransport: Send + Sync {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>>;
+
+    /// Executes a request with a streamed body, see `WebhookClient::send_streaming`.
+    ///
+    /// The default implementation reads the whole body into memory and calls `execute`;
+    /// the hyper transport streams it.
+    fn execute_stream(
+        &self,
+        request: http::Request<HttpBodyStream>,
+    ) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = body
+                .try_fold(Vec::new(), |mut body, chunk| async move {
+                    body.extend_from_slice(&chunk);
+                    Ok(body)
+                })
+                .await?;
+            self.execute(HttpRequest::from_parts(parts, body)).await
+        })
+    }
+
+    /// Executes a request whose response body may not be larger than `max_body_size` bytes,
+    /// failing with `BodyTooLarge` otherwise, e.g. when downloading a file attached by URL.
+    ///
+    /// The default implementation reads the whole body and checks its size afterwards; the
+    /// hyper and async-io transports reject a larger `Content-Length` before reading and stop
+    /// reading once the body exceeds the limit.
+    fn execute_limited(
+        &self,
+        request: HttpRequest,
+        max_body_size: usize,
+    ) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        Box::pin(async move {
+            let response = self.execute(request).await?;
+            if response.body().len() > max_body_size {
+                return Err(BodyTooLarge::new(max_body_size).into());
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// The `Content-Length` of a response, if it declares one.
+#[cfg(any(feature = "client", feature = "async-io"))]
+fn content_length(headers: &http::HeaderMap) -> Option<usize> {
+    headers
+        .get(http::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Connection settings collected by the `WebhookClientBuilder`.
+///
+/// The built-in backends apply the settings they support; the hyper backend supports all of them,
+/// the async-io and wasm backends ignore the connection settings and reject proxies, and the wasm
+/// backend rejects TLS settings, which are up to the browser.
+#[derive(Debug, Clone)]
+pub(crate) struct TransportConfig {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    pub(crate) pool_idle_timeout: Option<Duration>,
+    pub(crate) tcp_keepalive: Option<Duration>,
+    pub(crate) http2_only: bool,
+    /// Additional trusted root certificates, PEM or DER encoded.
+    pub(crate) root_certificates: Vec<Vec<u8>>,
+    pub(crate) accept_invalid_certs: bool,
+}
+
+impl TransportConfig {
+    /// How long a request may take by default, see `WebhookClientBuilder::timeout`.
+    pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// The additional root certificates, parsed.
+    #[cfg(any(feature = "client", feature = "async-io"))]
+    pub(crate) fn parse_root_certificates(&self) -> WebhookResult<Vec<native_tls::Certificate>> {
+        self.root_certificates
+            .iter()
+            .map(|certificate| {
+                let parsed = if certificate.starts_with(b"-----BEGIN") {
+                    native_tls::Certificate::from_pem(certificate)
+                } else {
+                    native_tls::Certificate::from_der(certificate)
+                };
+                parsed.map_err(|e| format!("Invalid root certificate: {}", e).into())
+            })
+            .collect()
+    }
+
+    /// A TLS connector trusting the additional root certificates.
+    #[cfg(feature = "client")]
+    pub(crate) fn tls_connector(&self) -> WebhookResult<native_tls::TlsConnector> {
+        let mut builder = native_tls::TlsConnector::builder();
+        for certificate in self.parse_root_certificates()? {
+            builder.add_root_certificate(certificate);
+        }
+        builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+        Ok(builder.build()?)
+    }
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Some(Self::DEFAULT_TIMEOUT),
+            proxy: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            tcp_keepalive: None,
+            http2_only: false,
+            root_certificates: Vec::new(),
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+/// A request the built-in transport gave up on because no complete response arrived within
+/// the timeout of the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout {
+    after: Duration,
+}
+
+impl Timeout {
+    /// The timeout that expired.
+    pub fn after(&self) -> Duration {
+        self.after
+    }
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request timed out after {:?}", self.after)
+    }
+}
+
+impl Error for Timeout {}
+
+/// A response body larger than the limit of `WebhookTransport::execute_limited`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyTooLarge {
+    limit: usize,
+}
+
+impl BodyTooLarge {
+    pub fn new(limit: usize) -> Self {
+        BodyTooLarge { limit }
+    }
+
+    /// The limit in bytes the body exceeded.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl fmt::Display for BodyTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "response body is larger than {} bytes", self.limit)
+    }
+}
+
+impl Error for BodyTooLarge {}
@@ -0,0 +1,199 @@
+//! A hyper connector opening connections through a SOCKS5 proxy (RFC 1928), with optional
+//! username and password authentication (RFC 1929).
+
+use futures_util::future::BoxFuture;
+use hyper::service::Service;
+use hyper::Uri;
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, Result};
+use std::net::IpAddr;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{self, TcpStream};
+
+const VERSION: u8 = 5;
+const NO_AUTHENTICATION: u8 = 0;
+const USERNAME_PASSWORD: u8 = 2;
+const CONNECT: u8 = 1;
+const IPV4: u8 = 1;
+const DOMAIN_NAME: u8 = 3;
+const IPV6: u8 = 4;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Socks5Connector {
+    host: String,
+    port: u16,
+    credentials: Option<(String, String)>,
+    /// Whether the proxy resolves the host names (`socks5h://`) instead of the client.
+    remote_dns: bool,
+}
+
+impl Socks5Connector {
+    /// A connector for a `socks5://` or `socks5h://` proxy URL, credentials included.
+    pub(crate) fn new(proxy: &Uri, credentials: Option<(String, String)>) -> Result<Self> {
+        let remote_dns = match proxy.scheme_str() {
+            Some("socks5") => false,
+            Some("socks5h") => true,
+            _ => {
+                return Err(invalid(
+                    "the proxy URL must start with socks5:// or socks5h://",
+                ))
+            }
+        };
+        let host = proxy
+            .host()
+            .ok_or_else(|| invalid("the proxy URL has no host"))?;
+        Ok(Self {
+            host: host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_owned(),
+            port: proxy.port_u16().unwrap_or(1080),
+            credentials,
+            remote_dns,
+        })
+    }
+
+    async fn connect(self, target: Uri) -> Result<TcpStream> {
+        let host = target
+            .host()
+            .ok_or_else(|| invalid("the request URL has no host"))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_owned();
+        let port = target
+            .port_u16()
+            .unwrap_or(if target.scheme_str() == Some("https") {
+                443
+            } else {
+                80
+            });
+        let address = match host.parse::<IpAddr>() {
+            Ok(ip) => ip_address(ip),
+            Err(_) if self.remote_dns => {
+                let length =
+                    u8::try_from(host.len()).map_err(|_| invalid("the host name is too long"))?;
+                let mut address = vec![DOMAIN_NAME, length];
+                address.extend_from_slice(host.as_bytes());
+                address
+            }
+            Err(_) => {
+                let resolved = net::lookup_host((host.as_str(), port))
+                    .await?
+                    .next()
+                    .ok_or_else(|| {
+                        Error::new(ErrorKind::NotFound, format!("{} did not resolve", host))
+                    })?;
+                ip_address(resolved.ip())
+            }
+        };
+
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        self.authenticate(&mut stream).await?;
+
+        let mut request = vec![VERSION, CONNECT, 0];
+        request.extend_from_slice(&address);
+        request.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0 {
+            return Err(Error::new(
+                ErrorKind::ConnectionRefused,
+                format!(
+                    "the SOCKS5 proxy refused the connection: {}",
+                    reply_message(reply[1])
+                ),
+            ));
+        }
+        // the address the proxy bound, which is of no use here
+        let bound_length = match reply[3] {
+            IPV4 => 4,
+            IPV6 => 16,
+            DOMAIN_NAME => stream.read_u8().await? as usize,
+            _ => return Err(invalid("the SOCKS5 proxy sent an unknown address type")),
+        };
+        let mut bound = vec![0; bound_length + 2];
+        stream.read_exact(&mut bound).await?;
+
+        Ok(stream)
+    }
+
+    async fn authenticate(&self, stream: &mut TcpStream) -> Result<()> {
+        let method = match self.credentials {
+            Some(_) => USERNAME_PASSWORD,
+            None => NO_AUTHENTICATION,
+        };
+        stream.write_all(&[VERSION, 1, method]).await?;
+        let mut choice = [0; 2];
+        stream.read_exact(&mut choice).await?;
+        if choice[0] != VERSION {
+            return Err(invalid("the proxy is not a SOCKS5 proxy"));
+        }
+        if choice[1] != method {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "the SOCKS5 proxy requires another authentication method",
+            ));
+        }
+
+        if let Some((username, password)) = self.credentials.as_ref() {
+            let too_long = || invalid("the SOCKS5 username and password are limited to 255 bytes");
+            let mut request = vec![1, u8::try_from(username.len()).map_err(|_| too_long())?];
+            request.extend_from_slice(username.as_bytes());
+            request.push(u8::try_from(password.len()).map_err(|_| too_long())?);
+            request.extend_from_slice(password.as_bytes());
+            stream.write_all(&request).await?;
+
+            let mut status = [0; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0 {
+                return Err(Error::new(
+                    ErrorKind::PermissionDenied,
+                    "the SOCKS5 proxy rejected the credentials",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Service<Uri> for Socks5Connector {
+    type Response = TcpStream;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<TcpStream>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, target: Uri) -> Self::Future {
+        Box::pin(self.clone().connect(target))
+    }
+}
+
+fn ip_address(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => [&[IPV4][..], &ip.octets()].concat(),
+        IpAddr::V6(ip) => [&[IPV6][..], &ip.octets()].concat(),
+    }
+}
+
+fn reply_message(reply: u8) -> &'static str {
+    match reply {
+        1 => "general failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidInput, message.to_owned())
+}
@@ -0,0 +1,73 @@
+use futures_util::future::{self, BoxFuture, Either};
+use gloo_net::http::{Method, RequestBuilder};
+use gloo_timers::future::TimeoutFuture;
+use send_wrapper::SendWrapper;
+use std::convert::TryFrom;
+
+use crate::transport::{HttpRequest, HttpResponse, Timeout, TransportConfig, WebhookTransport};
+use crate::WebhookResult;
+
+/// Transport for `wasm32-unknown-unknown`, sending requests through the `fetch` API.
+///
+/// Connections, TLS and pooling are handled by the host (browser or worker), so only the timeout
+/// setting has an effect. Proxies are rejected.
+pub(crate) struct WasmTransport {
+    config: TransportConfig,
+}
+
+impl WasmTransport {
+    pub(crate) fn new(config: &TransportConfig) -> WebhookResult<Self> {
+        if config.proxy.is_some() {
+            return Err("Proxies are not supported by the wasm transport".into());
+        }
+        if !config.root_certificates.is_empty() || config.accept_invalid_certs {
+            return Err("TLS settings are not supported by the wasm transport".into());
+        }
+        Ok(WasmTransport {
+            config: config.clone(),
+        })
+    }
+
+    async fn execute_with_timeout(&self, request: HttpRequest) -> WebhookResult<HttpResponse> {
+        let timeout = match self.config.timeout {
+            None => return self.execute_inner(request).await,
+            Some(timeout) => timeout,
+        };
+
+        let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+        let execution = Box::pin(self.execute_inner(request));
+        match future::select(execution, TimeoutFuture::new(millis)).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(Timeout { after: timeout }.into()),
+        }
+    }
+
+    async fn execute_inner(&self, request: HttpRequest) -> WebhookResult<HttpResponse> {
+        let (parts, body) = request.into_parts();
+
+        let mut outgoing = RequestBuilder::new(&parts.uri.to_string())
+            .method(Method::from_bytes(parts.method.as_str().as_bytes())?);
+        for (name, value) in parts.headers.iter() {
+            outgoing = outgoing.header(name.as_str(), value.to_str()?);
+        }
+        let outgoing = outgoing
+            .body(js_sys::Uint8Array::from(body.as_slice()))
+            .map_err(|error| error.to_string())?;
+
+        let incoming = outgoing.send().await.map_err(|error| error.to_string())?;
+        let mut response = http::Response::builder().status(incoming.status());
+        for (name, value) in incoming.headers().entries() {
+            response = response.header(name, value);
+        }
+        let body = incoming.binary().await.map_err(|error| error.to_string())?;
+
+        Ok(response.body(body)?)
+    }
+}
+
+impl WebhookTransport for WasmTransport {
+    fn execute(&self, request: HttpRequest) -> BoxFuture<'_, WebhookResult<HttpResponse>> {
+        // wasm32 is single threaded, the JS futures never actually cross threads.
+        Box::pin(SendWrapper::new(self.execute_with_timeout(request)))
+    }
+}
@@ -0,0 +1,99 @@
+//! Files streamed from disk or any other reader while a message is sent.
+
+use bytes::Bytes;
+use futures_util::future::poll_fn;
+use futures_util::stream::{self, StreamExt};
+use std::fmt;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::models::{Attachment, Snowflake};
+use crate::transport::HttpBodyStream;
+
+/// A file for `WebhookClient::send_streaming`, read in chunks while the request is sent
+/// instead of being loaded into memory.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # async fn run(client: WebhookClient, message: Message) -> WebhookResult<()> {
+/// let file = tokio::fs::File::open("build.log").await?;
+/// let length = file.metadata().await?.len();
+/// let mut upload = FileStream::new("build.log", file);
+/// upload.length(length).description("Full build output");
+/// client.send_streaming(&message, vec![upload]).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FileStream {
+    pub(crate) attachment: Attachment,
+    pub(crate) length: Option<u64>,
+    reader: Box<dyn AsyncRead + Send + Unpin>,
+}
+
+impl FileStream {
+    pub fn new<Reader>(filename: impl Into<String>, reader: Reader) -> Self
+    where
+        Reader: AsyncRead + Send + Unpin + 'static,
+    {
+        Self {
+            attachment: Attachment::new(Snowflake::new(0), filename, None),
+            length: None,
+            reader: Box::new(reader),
+        }
+    }
+
+    /// The size of the file in bytes. If every streamed file has a length, the request is
+    /// sent with a `Content-Length` header instead of chunked.
+    pub fn length(&mut self, length: u64) -> &mut Self {
+        self.length = Some(length);
+        self
+    }
+
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.attachment.description(description);
+        self
+    }
+
+    /// Hides the file behind a spoiler by prefixing its name with `SPOILER_`.
+    pub fn spoiler(&mut self) -> &mut Self {
+        self.attachment.spoiler();
+        self
+    }
+
+    /// The file contents in chunks of up to `CHUNK_SIZE` bytes.
+    pub(crate) fn into_chunks(self) -> HttpBodyStream {
+        stream::unfold(Some(self.reader), |reader| async move {
+            let mut reader = reader?;
+            let mut chunk = vec![0; Self::CHUNK_SIZE];
+            let read = poll_fn(|context| {
+                let mut buffer = ReadBuf::new(&mut chunk);
+                Pin::new(&mut reader)
+                    .poll_read(context, &mut buffer)
+                    .map_ok(|()| buffer.filled().len())
+            })
+            .await;
+            match read {
+                Ok(0) => None,
+                Ok(length) => {
+                    chunk.truncate(length);
+                    Some((Ok(Bytes::from(chunk)), Some(reader)))
+                }
+                // the body ends with the error
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+        .boxed()
+    }
+
+    const CHUNK_SIZE: usize = 64 * 1024;
+}
+
+impl fmt::Debug for FileStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileStream")
+            .field("attachment", &self.attachment)
+            .field("length", &self.length)
+            .finish()
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::WebhookResult;
+
+const HOSTS: [&str; 6] = [
+    "discord.com",
+    "discordapp.com",
+    "canary.discord.com",
+    "canary.discordapp.com",
+    "ptb.discord.com",
+    "ptb.discordapp.com",
+];
+
+/// The id and token of a webhook, parsed from an execute URL such as
+/// `https://discord.com/api/webhooks/{id}/{token}`.
+///
+/// Example
+/// ```no_run
+/// # use webhook::prelude::*;
+/// # fn main() -> WebhookResult<()> {
+/// let url = WebhookUrl::parse(&std::env::var("WEBHOOK_URL")?)?;
+/// let client = WebhookClient::from_id_token(url.id(), url.token());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookUrl {
+    id: String,
+    token: String,
+}
+
+impl WebhookUrl {
+    pub fn new(id: &str, token: &str) -> Self {
+        WebhookUrl {
+            id: id.to_owned(),
+            token: token.to_owned(),
+        }
+    }
+
+    /// Parses a webhook URL copied from Discord. The API version segment (`/api/v10/...`),
+    /// the canary and ptb hosts and a query string are accepted.
+    pub fn parse(url: &str) -> WebhookResult<Self> {
+        let rest = url
+            .strip_prefix("https://")
+            .ok_or_else(|| format!("Webhook URL must start with https://: {}", url))?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if !HOSTS.contains(&host) {
+            return Err(format!("Webhook URL host must be discord.com, not {}", host).into());
+        }
+
+        let mut segments = path.trim_end_matches('/').split('/');
+        if segments.next() != Some("api") {
+            return Err(format!("Webhook URL path must start with /api/: {}", url).into());
+        }
+        let mut segment = segments.next();
+        if segment.is_some_and(is_api_version) {
+            segment = segments.next();
+        }
+        if segment != Some("webhooks") {
+            return Err(format!("Webhook URL path must contain /webhooks/: {}", url).into());
+        }
+
+        match (segments.next(), segments.next(), segments.next()) {
+            (Some(id), Some(token), None) => {
+                if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(format!("Webhook id must be numeric, not {:?}", id).into());
+                }
+                if token.is_empty() || !token.chars().all(is_token_char) {
+                    return Err("Webhook token contains invalid characters".into());
+                }
+                Ok(WebhookUrl::new(id, token))
+            }
+            _ => Err(format!(
+                "Webhook URL must end with /webhooks/{{id}}/{{token}}: {}",
+                url
+            )
+            .into()),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+/// `url` with the token of the webhook replaced by `***`, so it can be logged. URLs without a
+/// `/webhooks/{id}/{token}` path are returned unchanged.
+pub fn redact_token(url: &str) -> String {
+    let id = match url.find("/webhooks/") {
+        Some(start) => start + "/webhooks/".len(),
+        None => return url.to_owned(),
+    };
+    let token = match url[id..].find('/') {
+        Some(slash) => id + slash + 1,
+        None => return url.to_owned(),
+    };
+    let end = url[token..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |end| token + end);
+    format!("{}***{}", &url[..token], &url[end..])
+}
+
+fn is_api_version(segment: &str) -> bool {
+    segment
+        .strip_prefix('v')
+        .is_some_and(|version| !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+impl fmt::Display for WebhookUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "https://discord.com/api/webhooks/{}/{}",
+            self.id, self.token
+        )
+    }
+}
+
+impl FromStr for WebhookUrl {
+    type Err = Box<dyn std::error::Error + Send + Sync>;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        Self::parse(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact_token, WebhookUrl};
+
+    #[test]
+    fn urls_are_parsed() {
+        let url =
+            WebhookUrl::parse("https://discordapp.com/api/v10/webhooks/123/abc-DEF_9?wait=true")
+                .unwrap();
+        assert_eq!(url.id(), "123");
+        assert_eq!(url.token(), "abc-DEF_9");
+        assert_eq!(
+            url.to_string(),
+            "https://discord.com/api/webhooks/123/abc-DEF_9"
+        );
+        assert!(WebhookUrl::parse("https://canary.discord.com/api/webhooks/1/t/").is_ok());
+    }
+
+    #[test]
+    fn malformed_urls_are_rejected() {
+        for (url, reason) in [
+            ("http://discord.com/api/webhooks/1/t", "https"),
+            ("https://example.com/api/webhooks/1/t", "host"),
+            ("https://discord.com/webhooks/1/t", "/api/"),
+            ("https://discord.com/api/channels/1/t", "/webhooks/"),
+            ("https://discord.com/api/webhooks/1", "{id}/{token}"),
+            ("https://discord.com/api/webhooks/abc/t", "numeric"),
+            ("https://discord.com/api/webhooks/1/t%20", "token"),
+        ] {
+            let error = WebhookUrl::parse(url).unwrap_err().to_string();
+            assert!(error.contains(reason), "{}: {}", url, error);
+        }
+    }
+
+    #[test]
+    fn tokens_are_redacted() {
+        assert_eq!(
+            redact_token("https://discord.com/api/webhooks/1/secret"),
+            "https://discord.com/api/webhooks/1/***"
+        );
+        assert_eq!(
+            redact_token("https://discord.com/api/webhooks/1/secret/messages/7?thread_id=5"),
+            "https://discord.com/api/webhooks/1/***/messages/7?thread_id=5"
+        );
+        assert_eq!(
+            redact_token("https://example.com/hook"),
+            "https://example.com/hook"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use futures_util::stream::{self, StreamExt};
+use http::{Method, Request, StatusCode};
+use std::time::Duration;
+
+use crate::client::WebhookResult;
+use crate::models::Webhook;
+use crate::request::retry_after;
+use crate::transport::{DefaultTransport, HttpResponse, TransportConfig, WebhookTransport};
+use crate::url::WebhookUrl;
+
+/// The outcome of probing a single webhook URL.
+// a report holds one status per URL, so the size of the valid variant does not matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum WebhookStatus {
+    /// The webhook exists and the token is accepted.
+    Valid(Webhook),
+    /// The URL is not a Discord webhook URL, or Discord rejected the token.
+    Invalid(String),
+    /// Discord does not know the webhook (anymore).
+    Deleted,
+    /// The webhook could not be probed, e.g. due to a network error or an outage.
+    /// The webhook may still be valid; probe it again later.
+    Failed(String),
+    /// Discord rate limited the probe; probe the webhook again after the duration.
+    RateLimited(Duration),
+}
+
+/// Results of `verify_all`, in the order the URLs were given.
+#[derive(Debug)]
+pub struct VerificationReport {
+    pub results: Vec<(String, WebhookStatus)>,
+}
+
+impl VerificationReport {
+    pub fn valid(&self) -> impl Iterator<Item = (&str, &Webhook)> {
+        self.results
+            .iter()
+            .filter_map(|(url, status)| match status {
+                WebhookStatus::Valid(webhook) => Some((url.as_str(), webhook)),
+                _ => None,
+            })
+    }
+
+    pub fn invalid(&self) -> impl Iterator<Item = &str> {
+        self.urls_matching(|status| matches!(status, WebhookStatus::Invalid(_)))
+    }
+
+    pub fn deleted(&self) -> impl Iterator<Item = &str> {
+        self.urls_matching(|status| matches!(status, WebhookStatus::Deleted))
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &str> {
+        self.urls_matching(|status| matches!(status, WebhookStatus::Failed(_)))
+    }
+
+    pub fn rate_limited(&self) -> impl Iterator<Item = &str> {
+        self.urls_matching(|status| matches!(status, WebhookStatus::RateLimited(_)))
+    }
+
+    fn urls_matching<Pred>(&self, predicate: Pred) -> impl Iterator<Item = &str>
+    where
+        Pred: Fn(&WebhookStatus) -> bool,
+    {
+        self.results
+            .iter()
+            .filter(move |(_, status)| predicate(status))
+            .map(|(url, _)| url.as_str())
+    }
+}
+
+/// Probes every webhook URL with a `GET` request, running at most `max_concurrency` probes
+/// at the same time over a shared connection pool.
+///
+/// Only Discord is contacted: URLs that `WebhookUrl::parse` rejects, e.g. ones pointing at
+/// another host, are reported as `Invalid` without a request, and the others are probed at
+/// their canonical `https://discord.com/api/webhooks/{id}/{token}` form.
+///
+/// Example
+/// ```no_run
+/// # async fn run(stored_urls: Vec<String>) -> webhook::WebhookResult<()> {
+/// let report = webhook::verify::verify_all(stored_urls, 8).await?;
+/// for url in report.deleted() {
+///     println!("{} was deleted", url);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn verify_all<I, S>(urls: I, max_concurrency: usize) -> WebhookResult<VerificationReport>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let transport = DefaultTransport::new(&TransportConfig::default())?;
+    let transport = &transport;
+
+    let results = stream::iter(urls.into_iter().map(Into::into))
+        .map(|url: String| async move {
+            let status = probe(transport, &url).await;
+            (url, status)
+        })
+        .buffered(max_concurrency.max(1))
+        .collect()
+        .await;
+
+    Ok(VerificationReport { results })
+}
+
+async fn probe(transport: &DefaultTransport, url: &str) -> WebhookStatus {
+    let url = match WebhookUrl::parse(url) {
+        Ok(url) => url,
+        Err(err) => return WebhookStatus::Invalid(err.to_string()),
+    };
+    let request = match Request::builder()
+        .method(Method::GET)
+        .uri(url.to_string())
+        .body(vec![])
+    {
+        Ok(request) => request,
+        Err(err) => return WebhookStatus::Invalid(format!("Malformed URL: {}", err)),
+    };
+
+    match transport.execute(request).await {
+        Ok(response) => classify(&response),
+        Err(err) => WebhookStatus::Failed(err.to_string()),
+    }
+}
+
+fn classify(response: &HttpResponse) -> WebhookStatus {
+    let body = || String::from_utf8_lossy(response.body()).into_owned();
+    match response.status() {
+        StatusCode::OK => match serde_json::from_slice(response.body()) {
+            Ok(webhook) => WebhookStatus::Valid(webhook),
+            Err(err) => WebhookStatus::Failed(format!("Unexpected webhook payload: {}", err)),
+        },
+        StatusCode::NOT_FOUND => WebhookStatus::Deleted,
+        StatusCode::TOO_MANY_REQUESTS => WebhookStatus::RateLimited(retry_after(response)),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::BAD_REQUEST => {
+            WebhookStatus::Invalid(body())
+        }
+        _ => WebhookStatus::Failed(body()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, WebhookStatus};
+    use crate::transport::HttpResponse;
+    use std::time::Duration;
+
+    fn response(status: u16, body: &str) -> HttpResponse {
+        http::Response::builder()
+            .status(status)
+            .body(body.as_bytes().to_vec())
+            .unwrap()
+    }
+
+    #[test]
+    fn responses_are_classified() {
+        let webhook = r#"{"id":"1","type":1,"guild_id":"2","channel_id":"3","name":null,"avatar":null,"token":"t","application_id":null}"#;
+        assert!(matches!(
+            classify(&response(200, webhook)),
+            WebhookStatus::Valid(_)
+        ));
+        assert!(matches!(
+            classify(&response(404, "")),
+            WebhookStatus::Deleted
+        ));
+        assert!(matches!(
+            classify(&response(401, "")),
+            WebhookStatus::Invalid(_)
+        ));
+        assert!(matches!(
+            classify(&response(502, "")),
+            WebhookStatus::Failed(_)
+        ));
+        assert!(matches!(
+            classify(&response(429, r#"{"retry_after": 2.5}"#)),
+            WebhookStatus::RateLimited(wait) if wait == Duration::from_millis(2500)
+        ));
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn only_discord_urls_are_probed() {
+        use super::verify_all;
+
+        let urls = [
+            "http://169.254.169.254/latest/meta-data/",
+            "https://discord.com.attacker.test/api/webhooks/1/token",
+            "https://discord.com/api/webhooks/1/token/../../../users/@me",
+        ];
+        let report = verify_all(urls, 2).await.unwrap();
+        assert_eq!(report.invalid().collect::<Vec<_>>(), urls);
+    }
+}
@@ -0,0 +1,193 @@
+//! An `AsyncWrite` that posts the written text to a webhook, so code writing logs to a writer
+//! can write them to Discord instead.
+//!
+//! Example
+//! ```no_run
+//! # use std::process::Stdio;
+//! # use tokio::io::AsyncWriteExt;
+//! # use tokio::process::Command;
+//! # use webhook::prelude::*;
+//! # use webhook::queue::WebhookQueue;
+//! # use webhook::writer::WebhookWriter;
+//! # async fn run(url: &str) -> WebhookResult<()> {
+//! let queue = WebhookQueue::spawn(WebhookClient::new(url));
+//! let mut writer = WebhookWriter::new(&queue);
+//! writer.language("log");
+//! let mut child = Command::new("cargo").arg("build").stderr(Stdio::piped()).spawn()?;
+//! tokio::io::copy(child.stderr.as_mut().unwrap(), &mut writer).await?;
+//! writer.shutdown().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::{ready, Sink};
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+
+use crate::fmt::{codeblock, split_text};
+use crate::models::Message;
+use crate::queue::{QueueSink, WebhookQueue};
+use crate::WebhookError;
+
+/// Buffers the written bytes and sends them through a `WebhookQueue` as code block messages.
+///
+/// Text is sent once the buffer holds more than fits into one message, cut at line breaks, and
+/// when the writer is flushed. Shutting the writer down waits until everything written was
+/// sent. Writes wait while the queue is full.
+pub struct WebhookWriter {
+    sink: QueueSink,
+    language: String,
+    max_chars: usize,
+    buffer: Vec<u8>,
+    /// Messages waiting for room in the queue.
+    pending: VecDeque<Message>,
+}
+
+impl WebhookWriter {
+    /// The default number of characters of text per message, leaving room for the code block
+    /// fences within the content limit.
+    pub const DEFAULT_MAX_CHARS: usize = 1900;
+
+    pub fn new(queue: &WebhookQueue) -> Self {
+        WebhookWriter {
+            sink: queue.sink(),
+            language: String::new(),
+            max_chars: Self::DEFAULT_MAX_CHARS,
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// The language of the code blocks for syntax highlighting, none by default.
+    pub fn language(&mut self, language: &str) -> &mut Self {
+        self.language = language.to_owned();
+        self
+    }
+
+    /// The maximum number of characters of text per message. Text that does not fit into
+    /// the content of a message together with the code block fences is cut off.
+    pub fn max_chars(&mut self, max_chars: usize) -> &mut Self {
+        self.max_chars = max_chars.max(1);
+        self
+    }
+
+    /// Turns buffered text into messages: everything if `all`, otherwise the complete lines
+    /// once the buffer holds more than fits into one message.
+    fn take_messages(&mut self, all: bool) {
+        let end = if all {
+            self.buffer.len()
+        } else if self.buffer.len() <= self.max_chars {
+            return;
+        } else {
+            match self.buffer.iter().rposition(|&byte| byte == b'\n') {
+                Some(newline) => newline + 1,
+                // a single long line, cut where no character is split
+                None => match std::str::from_utf8(&self.buffer) {
+                    Err(err) if err.error_len().is_none() => err.valid_up_to(),
+                    _ => self.buffer.len(),
+                },
+            }
+        };
+        let taken: Vec<u8> = self.buffer.drain(..end).collect();
+        let text = String::from_utf8_lossy(&taken);
+        for chunk in split_text(text.trim_end_matches('\n'), self.max_chars) {
+            if chunk.trim().is_empty() {
+                continue;
+            }
+            let mut message = Message::new();
+            message.content(codeblock(
+                &self.language,
+                &chunk,
+                Message::CONTENT_LEN_INTERVAL.max_allowed,
+            ));
+            self.pending.push_back(message);
+        }
+    }
+
+    /// Moves the pending messages into the queue.
+    fn poll_send_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.pending.is_empty() {
+            ready!(Pin::new(&mut self.sink).poll_ready(cx)).map_err(io_error)?;
+            let message = self.pending.pop_front().expect("pending is not empty");
+            Pin::new(&mut self.sink)
+                .start_send(message)
+                .map_err(io_error)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for WebhookWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bytes: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let writer = self.get_mut();
+        ready!(writer.poll_send_pending(cx))?;
+        writer.buffer.extend_from_slice(bytes);
+        writer.take_messages(false);
+        // the bytes are taken either way, the rest is sent by the next write or flush
+        if let Poll::Ready(Err(err)) = writer.poll_send_pending(cx) {
+            return Poll::Ready(Err(err));
+        }
+        Poll::Ready(Ok(bytes.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let writer = self.get_mut();
+        writer.take_messages(true);
+        ready!(writer.poll_send_pending(cx))?;
+        Pin::new(&mut writer.sink).poll_flush(cx).map_err(io_error)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        Pin::new(&mut self.get_mut().sink)
+            .poll_close(cx)
+            .map_err(io_error)
+    }
+}
+
+fn io_error(err: Box<WebhookError>) -> io::Error {
+    io::Error::other(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebhookWriter;
+    use crate::queue::WebhookQueue;
+    use crate::testing::MockTransport;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn written_lines_are_sent_as_code_blocks() {
+        let mock = MockTransport::new();
+        let queue = WebhookQueue::spawn(mock.client());
+        let mut writer = WebhookWriter::new(&queue);
+        writer.language("log").max_chars(20);
+
+        writer.write_all(b"started\nlisten").await.unwrap();
+        assert!(mock.requests().is_empty());
+        writer.write_all(b"ing on :8080\nready").await.unwrap();
+        writer.write_all("\n\u{e9}".as_bytes()).await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        let contents: Vec<_> = mock
+            .messages()
+            .into_iter()
+            .filter_map(|message| message.content)
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                "```log\nstarted\n```",
+                "```log\nlistening on :8080\n```",
+                "```log\nready\n\u{e9}\n```",
+            ]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Derive macros for the `webhook` crate. Use them through its `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Expr, Field, Fields, LitBool, LitStr, Token, Type,
+};
+
+/// Implements `IntoEmbed` for a struct (by value and by reference), with one embed field per
+/// struct field.
+///
+/// Field values are formatted with `Display`; `Option` fields are left out when `None`.
+///
+/// Struct attributes: `#[embed(title = "...", color = 0xRRGGBB, inline)]`, where `inline`
+/// makes every field inline by default. Field attributes: `#[embed(name = "...")]` (the
+/// default is the field name in title case), `#[embed(inline)]`, `#[embed(inline = false)]`
+/// and `#[embed(skip)]`.
+///
+/// Example
+/// ```ignore
+/// #[derive(ToEmbed)]
+/// #[embed(title = "Nightly build", color = 0x57F287, inline)]
+/// struct BuildReport {
+///     commit: String,
+///     #[embed(name = "Duration (s)")]
+///     duration_secs: u64,
+///     #[embed(inline = false)]
+///     failed_tests: Option<String>,
+///     #[embed(skip)]
+///     log_path: PathBuf,
+/// }
+///
+/// client.send_item(&report).await?;
+/// ```
+#[proc_macro_derive(ToEmbed, attributes(embed))]
+pub fn derive_to_embed(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_embed(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct StructOptions {
+    title: Option<LitStr>,
+    color: Option<Expr>,
+    inline: bool,
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    name: Option<LitStr>,
+    inline: Option<bool>,
+    skip: bool,
+}
+
+fn expand_to_embed(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "ToEmbed needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ToEmbed can only be derived for structs",
+            ))
+        }
+    };
+
+    let options = struct_options(input)?;
+    let mut statements = Vec::new();
+    if let Some(title) = &options.title {
+        statements.push(quote!(embed.title(#title);));
+    }
+    if let Some(color) = &options.color {
+        statements.push(quote!(embed.color(#color as u32);));
+    }
+    for field in fields {
+        let field_options = field_options(field)?;
+        if field_options.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named fields have names");
+        let name = match field_options.name {
+            Some(name) => name.value(),
+            None => title_case(&ident.to_string()),
+        };
+        let inline = field_options.inline.unwrap_or(options.inline);
+        statements.push(if is_option(&field.ty) {
+            quote! {
+                if let ::std::option::Option::Some(value) = &self.#ident {
+                    embed.field(#name, value.to_string(), #inline);
+                }
+            }
+        } else {
+            quote!(embed.field(#name, self.#ident.to_string(), #inline);)
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::webhook::models::IntoEmbed for &#ident #type_generics #where_clause {
+            fn into_embed(self) -> ::webhook::models::Embed {
+                let mut embed = ::webhook::models::Embed::new();
+                #(#statements)*
+                embed
+            }
+        }
+
+        impl #impl_generics ::webhook::models::IntoEmbed for #ident #type_generics #where_clause {
+            fn into_embed(self) -> ::webhook::models::Embed {
+                ::webhook::models::IntoEmbed::into_embed(&self)
+            }
+        }
+    })
+}
+
+fn struct_options(input: &DeriveInput) -> syn::Result<StructOptions> {
+    let mut options = StructOptions::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("embed"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("title") {
+                options.title = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("color") {
+                options.color = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("inline") {
+                options.inline = true;
+            } else {
+                return Err(meta.error("expected `title`, `color` or `inline`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn field_options(field: &Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("embed"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                options.name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("inline") {
+                options.inline = Some(if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<LitBool>()?.value
+                } else {
+                    true
+                });
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else {
+                return Err(meta.error("expected `name`, `inline` or `skip`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// `duration_secs` becomes `Duration Secs`.
+fn title_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,48 @@
+use webhook::models::{Color, IntoEmbed, IntoMessage};
+use webhook::ToEmbed;
+
+#[derive(ToEmbed)]
+#[embed(title = "Nightly build", color = 0x57F287, inline)]
+struct BuildReport {
+    commit: &'static str,
+    #[embed(name = "Duration (s)")]
+    duration_secs: u64,
+    #[embed(inline = false)]
+    failed_tests: Option<String>,
+    flaky_tests: Option<String>,
+    #[embed(skip)]
+    #[allow(dead_code)]
+    log_path: &'static str,
+}
+
+#[test]
+fn fields_are_mapped() {
+    let report = BuildReport {
+        commit: "3e9b707",
+        duration_secs: 252,
+        failed_tests: Some("parser::nested".to_owned()),
+        flaky_tests: None,
+        log_path: "/tmp/build.log",
+    };
+
+    let embed = (&report).into_embed();
+    assert_eq!(embed.title.as_deref(), Some("Nightly build"));
+    assert_eq!(embed.color, Some(Color::GREEN));
+    let fields: Vec<(&str, &str, bool)> = embed
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.value.as_str(), field.inline))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("Commit", "3e9b707", true),
+            ("Duration (s)", "252", true),
+            ("Failed Tests", "parser::nested", false),
+        ]
+    );
+
+    let message = report.into_message();
+    assert_eq!(message.embeds.len(), 1);
+    assert!(message.validate().is_ok());
+}